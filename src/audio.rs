@@ -1,5 +1,8 @@
+use rodio::cpal::traits::HostTrait;
 use rodio::source::SineWave;
-use rodio::{Decoder, OutputStream, Sink, Source};
+use rodio::{
+    cpal, Decoder, DeviceTrait, OutputStream, OutputStreamHandle, Sink, Source, StreamError,
+};
 use std::borrow::Cow;
 use std::fs::File;
 use std::io::{BufReader, Cursor};
@@ -13,13 +16,26 @@ pub struct AudioPlayer {
 
 impl AudioPlayer {
     pub fn new() -> Self {
+        Self::with_output(None)
+    }
+
+    /// Player of the output device with given name, `None` for the default device
+    pub fn with_output(output: Option<String>) -> Self {
         let (tx, rx) = std::sync::mpsc::channel();
-        std::thread::spawn(audio_task(rx));
+        std::thread::spawn(audio_task(rx, output));
         AudioPlayer {
             handle: Mutex::new(tx),
         }
     }
 
+    /// Names of all available output devices
+    pub fn outputs() -> Vec<String> {
+        cpal::default_host()
+            .output_devices()
+            .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+            .unwrap_or_default()
+    }
+
     fn send(&self, command: PlayCommand) {
         if let Ok(h) = self.handle.lock() {
             h.send(command).unwrap_or(())
@@ -81,9 +97,23 @@ fn play_data(sink: &Sink, data: Cow<'static, [u8]>) {
     }
 }
 
-fn audio_task(rx: Receiver<PlayCommand>) -> impl FnOnce() -> () {
+fn open_stream(output: Option<String>) -> Result<(OutputStream, OutputStreamHandle), StreamError> {
+    let device = output.and_then(|output| {
+        cpal::default_host()
+            .output_devices()
+            .ok()?
+            .find(|d| d.name().map_or(false, |name| name == output))
+    });
+
+    match device {
+        Some(device) => OutputStream::try_from_device(&device),
+        None => OutputStream::try_default(),
+    }
+}
+
+fn audio_task(rx: Receiver<PlayCommand>, output: Option<String>) -> impl FnOnce() -> () {
     move || {
-        let (_stream, stream_handle) = OutputStream::try_default().unwrap();
+        let (_stream, stream_handle) = open_stream(output).unwrap();
         let sink = Sink::try_new(&stream_handle).unwrap();
         loop {
             if let Ok(command) = rx.recv() {
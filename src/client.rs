@@ -0,0 +1,123 @@
+use crate::game::Game;
+use crate::lifecycle::MatchState;
+use crate::session::Session;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+/// Game client sending game state updates to the announcer
+pub struct Client {
+    pub address: IpAddr,
+    pub session: Session,
+}
+
+#[derive(Serialize)]
+pub struct ClientInfo {
+    pub id: String,
+    pub name: Option<String>,
+    pub address: IpAddr,
+    pub state: MatchState,
+}
+
+/// Clients keyed by their id, each one with its own session
+pub struct Clients {
+    clients: HashMap<String, Client>,
+    /// Steam id last seen from the address, partial updates don't carry it
+    steamids: HashMap<IpAddr, String>,
+}
+
+impl Clients {
+    pub fn new() -> Self {
+        Clients {
+            clients: HashMap::new(),
+            steamids: HashMap::new(),
+        }
+    }
+
+    /// Steam id of the player last seen from the address, the address until there is any
+    pub fn id(&mut self, update: &Game, address: IpAddr) -> String {
+        let steamid = match update.player.as_ref().and_then(|p| p.steamid.clone()) {
+            Some(steamid) => steamid,
+            None => {
+                return self
+                    .steamids
+                    .get(&address)
+                    .cloned()
+                    .unwrap_or_else(|| address.to_string())
+            }
+        };
+
+        if self.steamids.insert(address, steamid.clone()).is_none() {
+            // client known by its address only keeps its session
+            if let Some(client) = self.clients.remove(&address.to_string()) {
+                self.clients.entry(steamid.clone()).or_insert(client);
+            }
+        }
+        steamid
+    }
+
+    pub fn get(&mut self, id: &str, address: IpAddr) -> &mut Client {
+        let client = self
+            .clients
+            .entry(id.to_string())
+            .or_insert_with(|| Client {
+                address,
+                session: Session::new(),
+            });
+        client.address = address;
+        client
+    }
+
+    pub fn info(&self) -> Vec<ClientInfo> {
+        self.clients
+            .iter()
+            .map(|(id, client)| ClientInfo {
+                id: id.clone(),
+                name: client
+                    .session
+                    .game()
+                    .player
+                    .as_ref()
+                    .and_then(|p| p.name.clone()),
+                address: client.address,
+                state: client.session.state(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::Clients;
+    use crate::game::Game;
+    use std::net::IpAddr;
+
+    fn update(json: &str) -> Game {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn id_of_partial_updates() {
+        let mut clients = Clients::new();
+        let local: IpAddr = "127.0.0.1".parse().unwrap();
+        let remote: IpAddr = "192.168.1.2".parse().unwrap();
+        let partial = update(r#"{"map": {"clock_time": 10}}"#);
+
+        assert_eq!(clients.id(&partial, local), "127.0.0.1");
+        clients.get("127.0.0.1", local);
+
+        // session of the address is kept once the steam id is known
+        let full = update(r#"{"player": {"steamid": "7656"}}"#);
+        assert_eq!(clients.id(&full, local), "7656");
+        assert_eq!(clients.info().len(), 1);
+        assert_eq!(clients.info()[0].id, "7656");
+
+        assert_eq!(clients.id(&partial, local), "7656");
+        assert_eq!(clients.id(&partial, remote), "192.168.1.2");
+
+        let other = update(r#"{"player": {"steamid": "7657"}}"#);
+        assert_eq!(clients.id(&other, remote), "7657");
+        assert_eq!(clients.id(&partial, remote), "7657");
+        assert_eq!(clients.id(&partial, local), "7656");
+    }
+}
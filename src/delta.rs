@@ -0,0 +1,353 @@
+use crate::game::{Game, EMPTY_ITEM};
+use crate::lifecycle::MatchState;
+
+/// Typed change of the game state
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    MatchStateChanged {
+        old: MatchState,
+        new: MatchState,
+    },
+    MatchStarted {
+        match_id: String,
+    },
+    MatchEnded {
+        match_id: String,
+    },
+    ClockTick {
+        old: i32,
+        new: i32,
+    },
+    GameStateChanged {
+        old: String,
+        new: String,
+    },
+    WardCooldownChanged {
+        old: i32,
+        new: i32,
+    },
+    GoldChanged {
+        old: u32,
+        new: u32,
+    },
+    ReliableGoldChanged {
+        old: u32,
+        new: u32,
+    },
+    BuybackCostChanged {
+        old: u32,
+        new: u32,
+    },
+    BuybackCooldownChanged {
+        old: u32,
+        new: u32,
+    },
+    HeroDied {
+        respawn_seconds: u32,
+    },
+    HeroRespawned,
+    LevelUp {
+        old: u8,
+        new: u8,
+    },
+    AbilityCooldownChanged {
+        slot: String,
+        name: String,
+        old: u32,
+        new: u32,
+    },
+    ItemAdded {
+        slot: String,
+        name: String,
+    },
+    ItemRemoved {
+        slot: String,
+        name: String,
+    },
+    ItemCooldownChanged {
+        slot: String,
+        name: String,
+        old: u32,
+        new: u32,
+    },
+}
+
+/// Changes carried by single game state update, built from its `previously` & `added` blocks
+#[derive(Debug, Default)]
+pub struct GameDelta {
+    pub events: Vec<GameEvent>,
+}
+
+impl GameDelta {
+    pub fn new(game: &Game) -> Self {
+        let mut delta = GameDelta::default();
+        delta.map(game);
+        delta.player(game);
+        delta.hero(game);
+        delta.abilities(game);
+        delta.items(game);
+        delta
+    }
+
+    fn push(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    fn map(&mut self, game: &Game) {
+        let (map, previous) = match (&game.map, &game.previously.map) {
+            (Some(map), Some(previous)) => (map, previous),
+            _ => return,
+        };
+
+        if let Some((old, new)) = previous.game_state.clone().zip(map.game_state.clone()) {
+            self.push(GameEvent::GameStateChanged { old, new });
+        }
+
+        if let Some((old, new)) = previous.clock_time.zip(map.clock_time) {
+            self.push(GameEvent::ClockTick { old, new });
+        }
+
+        if let Some((old, new)) = previous
+            .ward_purchase_cooldown
+            .zip(map.ward_purchase_cooldown)
+        {
+            self.push(GameEvent::WardCooldownChanged { old, new });
+        }
+    }
+
+    fn player(&mut self, game: &Game) {
+        let (player, previous) = match (&game.player, &game.previously.player) {
+            (Some(player), Some(previous)) => (player, previous),
+            _ => return,
+        };
+
+        if let Some((old, new)) = previous.gold.zip(player.gold) {
+            self.push(GameEvent::GoldChanged { old, new });
+        }
+
+        if let Some((old, new)) = previous.gold_reliable.zip(player.gold_reliable) {
+            self.push(GameEvent::ReliableGoldChanged { old, new });
+        }
+    }
+
+    fn hero(&mut self, game: &Game) {
+        let (hero, previous) = match (&game.hero, &game.previously.hero) {
+            (Some(hero), Some(previous)) => (hero, previous),
+            _ => return,
+        };
+
+        match previous.alive.zip(hero.alive) {
+            Some((true, false)) => self.push(GameEvent::HeroDied {
+                respawn_seconds: hero.respawn_seconds.unwrap_or_default(),
+            }),
+            Some((false, true)) => self.push(GameEvent::HeroRespawned),
+            _ => {}
+        }
+
+        if let Some((old, new)) = previous.level.zip(hero.level) {
+            if new > old {
+                self.push(GameEvent::LevelUp { old, new });
+            }
+        }
+
+        if let Some((old, new)) = previous.buyback_cost.zip(hero.buyback_cost) {
+            self.push(GameEvent::BuybackCostChanged { old, new });
+        }
+
+        if let Some((old, new)) = previous.buyback_cooldown.zip(hero.buyback_cooldown) {
+            self.push(GameEvent::BuybackCooldownChanged { old, new });
+        }
+    }
+
+    fn abilities(&mut self, game: &Game) {
+        let (abilities, previous) = match (&game.abilities, &game.previously.abilities) {
+            (Some(abilities), Some(previous)) => (abilities, previous),
+            _ => return,
+        };
+
+        for (slot, ability) in abilities {
+            let old = previous.get(slot).and_then(|a| a.cooldown);
+            if let Some((old, new)) = old.zip(ability.cooldown) {
+                self.push(GameEvent::AbilityCooldownChanged {
+                    slot: slot.clone(),
+                    name: ability.name.clone().unwrap_or_default(),
+                    old,
+                    new,
+                });
+            }
+        }
+    }
+
+    fn items(&mut self, game: &Game) {
+        let items = match &game.items {
+            Some(items) => items,
+            None => return,
+        };
+
+        for (slot, item) in items.slots() {
+            let item = match item {
+                Some(item) => item,
+                None => continue,
+            };
+
+            let name = item.name.clone().unwrap_or_default();
+            let previous = game.previously.items.as_ref().and_then(|i| i.slot(slot));
+
+            match previous.and_then(|p| p.name.clone()) {
+                Some(old_name) => {
+                    if old_name != EMPTY_ITEM {
+                        self.push(GameEvent::ItemRemoved {
+                            slot: slot.to_string(),
+                            name: old_name,
+                        });
+                    }
+                    if !item.is_empty() {
+                        self.push(GameEvent::ItemAdded {
+                            slot: slot.to_string(),
+                            name,
+                        });
+                    }
+                }
+                None => {
+                    if game.is_added(&format!("/items/{}", slot)) && !item.is_empty() {
+                        self.push(GameEvent::ItemAdded {
+                            slot: slot.to_string(),
+                            name: name.clone(),
+                        });
+                    }
+
+                    let old = previous.and_then(|p| p.cooldown);
+                    if let Some((old, new)) = old.zip(item.cooldown) {
+                        self.push(GameEvent::ItemCooldownChanged {
+                            slot: slot.to_string(),
+                            name,
+                            old,
+                            new,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::delta::{GameDelta, GameEvent};
+    use crate::game::Game;
+
+    fn delta(json: &str) -> Vec<GameEvent> {
+        let game: Game = serde_json::from_str(json).unwrap();
+        GameDelta::new(&game).events
+    }
+
+    #[test]
+    fn no_previously_no_events() {
+        let events = delta(r#"{"map": {"clock_time": 10}, "hero": {"alive": true}}"#);
+        assert_eq!(events, vec![]);
+    }
+
+    #[test]
+    fn clock_and_game_state() {
+        let events = delta(
+            r#"{
+                "map": {"clock_time": 10, "game_state": "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS"},
+                "previously": {"map": {"clock_time": 9, "game_state": "DOTA_GAMERULES_STATE_PRE_GAME"}}
+            }"#,
+        );
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::GameStateChanged {
+                    old: "DOTA_GAMERULES_STATE_PRE_GAME".to_string(),
+                    new: "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS".to_string(),
+                },
+                GameEvent::ClockTick { old: 9, new: 10 },
+            ]
+        );
+    }
+
+    #[test]
+    fn hero_died_and_respawned() {
+        let events = delta(
+            r#"{
+                "hero": {"alive": false, "respawn_seconds": 20},
+                "previously": {"hero": {"alive": true, "respawn_seconds": 0}}
+            }"#,
+        );
+        assert_eq!(
+            events,
+            vec![GameEvent::HeroDied {
+                respawn_seconds: 20
+            }]
+        );
+
+        let events = delta(
+            r#"{
+                "hero": {"alive": true, "level": 7},
+                "previously": {"hero": {"alive": false, "level": 6}}
+            }"#,
+        );
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::HeroRespawned,
+                GameEvent::LevelUp { old: 6, new: 7 }
+            ]
+        );
+    }
+
+    #[test]
+    fn item_moved_between_slots() {
+        let events = delta(
+            r#"{
+                "items": {
+                    "slot0": {"name": "empty"},
+                    "slot1": {"name": "item_blink", "cooldown": 3}
+                },
+                "previously": {"items": {
+                    "slot0": {"name": "item_blink", "cooldown": 3},
+                    "slot1": {"name": "empty"}
+                }}
+            }"#,
+        );
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::ItemRemoved {
+                    slot: "slot0".to_string(),
+                    name: "item_blink".to_string(),
+                },
+                GameEvent::ItemAdded {
+                    slot: "slot1".to_string(),
+                    name: "item_blink".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn item_cooldown_changed() {
+        let events = delta(
+            r#"{
+                "items": {"slot2": {"name": "item_blink", "cooldown": 0}},
+                "previously": {"items": {"slot2": {"cooldown": 1}}}
+            }"#,
+        );
+        assert_eq!(
+            events,
+            vec![GameEvent::ItemCooldownChanged {
+                slot: "slot2".to_string(),
+                name: "item_blink".to_string(),
+                old: 1,
+                new: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn section_reported_as_flag() {
+        let events = delta(r#"{"hero": {"alive": true}, "previously": {"hero": true}}"#);
+        assert_eq!(events, vec![]);
+    }
+}
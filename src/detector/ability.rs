@@ -0,0 +1,37 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+
+pub struct AbilityDetector;
+
+impl Detector for AbilityDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        let (slot, old) = match event {
+            GameEvent::AbilityCooldownChanged { slot, old, .. } => (slot, *old),
+            _ => return,
+        };
+
+        if !ctx.game.in_game() {
+            return;
+        }
+
+        let ability = match ctx.game.abilities.as_ref().and_then(|a| a.get(slot)) {
+            Some(ability) => ability,
+            None => return,
+        };
+
+        for config in &ctx.profile.abilities {
+            let notified = config.on_cooldown(ability, old, ctx.player);
+            for before_sec in &notified {
+                println!(
+                    "{} ability {} is ready in {} sec",
+                    ctx.clock_time(),
+                    config.ability,
+                    before_sec
+                );
+            }
+            if !notified.is_empty() {
+                break;
+            }
+        }
+    }
+}
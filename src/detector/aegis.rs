@@ -0,0 +1,168 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+use crate::game::{Game, EVENT_AEGIS_DENIED, EVENT_AEGIS_PICKED_UP, ITEM_AEGIS};
+
+/// Aegis disappears 5 minutes after being picked up
+const AEGIS_DURATION_SEC: i32 = 300;
+
+/// Announces aegis expiration based on `aegis_picked_up` events or aegis in the hero items
+#[derive(Default)]
+pub struct AegisDetector {
+    /// Clock time when the current aegis was picked up
+    picked_at: Option<i32>,
+    /// Clock time of the last pickup event already taken into account
+    last_pickup: Option<i32>,
+    /// Hero carried the aegis in the previous update
+    holding: bool,
+}
+
+impl AegisDetector {
+    fn update_picked_at(&mut self, clock_time: i32, game: &Game) {
+        let holding = game
+            .items
+            .as_ref()
+            .and_then(|items| items.find(ITEM_AEGIS))
+            .is_some();
+
+        let pickup = game.event_clock_times(EVENT_AEGIS_PICKED_UP).max();
+        if pickup.is_some() && pickup > self.last_pickup {
+            self.last_pickup = pickup;
+            self.picked_at = pickup;
+        } else if holding && !self.holding && self.picked_at.is_none() {
+            // Aegis may show up in the items before its pickup event (older game versions
+            // don't send the event at all), the update clock time is an estimate only
+            // replaced by the event time once the event arrives
+            self.picked_at = Some(clock_time);
+        }
+
+        if let Some(picked_at) = self.picked_at {
+            let denied = game
+                .event_clock_times(EVENT_AEGIS_DENIED)
+                .any(|at| at >= picked_at);
+            // aegis leaves the inventory when consumed on death
+            let consumed = self.holding && !holding;
+
+            if denied || consumed {
+                println!("{} aegis consumed or denied", clock_time);
+                self.picked_at = None;
+            }
+        }
+        self.holding = holding;
+    }
+
+    fn expires_at(&self) -> Option<i32> {
+        self.picked_at
+            .map(|picked_at| picked_at + AEGIS_DURATION_SEC)
+    }
+
+    fn expire(&mut self, clock_time: i32) {
+        if self.expires_at().map_or(false, |at| clock_time >= at) {
+            self.picked_at = None;
+        }
+    }
+}
+
+impl Detector for AegisDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        let (old, new) = match event {
+            GameEvent::ClockTick { old, new } => (*old, *new),
+            _ => return,
+        };
+
+        if !ctx.game.in_game() {
+            return;
+        }
+
+        self.update_picked_at(new, ctx.game);
+
+        let expires_at = match self.expires_at() {
+            Some(expires_at) => expires_at,
+            None => return,
+        };
+
+        let crossed = |before_sec: u16| {
+            let at = expires_at - before_sec as i32;
+            old < at && at <= new
+        };
+        let notify = &ctx.profile.aegis.expires.notify;
+        for before_sec in notify.trigger_leads(ctx.player, crossed) {
+            println!("{} aegis expires in {} sec", new, before_sec);
+        }
+
+        self.expire(new);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detector::aegis::AegisDetector;
+    use crate::game::Game;
+
+    /// Game with aegis in the items or not and optional event at given clock time
+    fn game(holding: bool, event: Option<(&str, i32)>) -> Game {
+        let items = if holding {
+            r#"{"slot0": {"name": "item_aegis"}}"#
+        } else {
+            r#"{"slot0": {"name": "empty"}}"#
+        };
+        let events = event.map_or(String::new(), |(event_type, at)| {
+            format!(
+                r#", "events": [{{"event_type": "{}", "game_time": {}}}]"#,
+                event_type,
+                at + 90
+            )
+        });
+        serde_json::from_str(&format!(
+            r#"{{"map": {{"clock_time": 1000, "game_time": 1090}}, "items": {}{}}}"#,
+            items, events
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn pickup_and_consume() {
+        let mut detector = AegisDetector::default();
+        detector.update_picked_at(1000, &game(true, Some(("aegis_picked_up", 995))));
+        assert_eq!(detector.expires_at(), Some(1295));
+
+        // the same event repeated in following updates
+        detector.update_picked_at(1001, &game(true, Some(("aegis_picked_up", 995))));
+        assert_eq!(detector.expires_at(), Some(1295));
+
+        // consumed on death
+        detector.update_picked_at(1100, &game(false, None));
+        assert_eq!(detector.expires_at(), None);
+    }
+
+    #[test]
+    fn pickup_without_event() {
+        let mut detector = AegisDetector::default();
+        detector.update_picked_at(1000, &game(true, None));
+        assert_eq!(detector.expires_at(), Some(1300));
+
+        // estimate replaced by the late event
+        detector.update_picked_at(1001, &game(true, Some(("aegis_picked_up", 998))));
+        assert_eq!(detector.expires_at(), Some(1298));
+    }
+
+    #[test]
+    fn denied() {
+        let mut detector = AegisDetector::default();
+        detector.update_picked_at(1000, &game(false, Some(("aegis_picked_up", 995))));
+        assert_eq!(detector.expires_at(), Some(1295));
+
+        detector.update_picked_at(1010, &game(false, Some(("aegis_denied", 1005))));
+        assert_eq!(detector.expires_at(), None);
+    }
+
+    #[test]
+    fn expiry() {
+        let mut detector = AegisDetector::default();
+        detector.update_picked_at(1000, &game(true, Some(("aegis_picked_up", 1000))));
+
+        detector.expire(1299);
+        assert_eq!(detector.expires_at(), Some(1300));
+        detector.expire(1300);
+        assert_eq!(detector.expires_at(), None);
+    }
+}
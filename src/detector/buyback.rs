@@ -0,0 +1,31 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+
+#[derive(Default)]
+pub struct BuybackDetector {
+    last_buyback: bool,
+}
+
+impl Detector for BuybackDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        match event {
+            GameEvent::ReliableGoldChanged { .. }
+            | GameEvent::BuybackCostChanged { .. }
+            | GameEvent::BuybackCooldownChanged { .. } => {}
+            _ => return,
+        }
+
+        let notify = &ctx.profile.buyback_ready.notify;
+        if !notify.enabled || !ctx.game.in_game() {
+            return;
+        }
+
+        let has_buyback = ctx.game.has_buyback();
+        if has_buyback && !self.last_buyback {
+            self.last_buyback = true;
+            notify.action.trigger(ctx.player);
+        } else if !has_buyback && self.last_buyback {
+            self.last_buyback = false;
+        }
+    }
+}
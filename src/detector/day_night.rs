@@ -0,0 +1,115 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+
+/// Day and night alternate every 5 minutes of the clock time
+const CYCLE_SEC: i32 = 300;
+
+/// Observed flip further from the schedule is an override (e.g. Luna's eclipse), not a drift
+const MAX_DRIFT_SEC: i32 = 30;
+
+/// Announces day/night flips scheduled by the clock time and corrected by the observed `daytime`
+#[derive(Default)]
+pub struct DayNightDetector {
+    daytime: Option<bool>,
+    /// Shift of the observed flips against the clock time schedule
+    drift: i32,
+}
+
+impl DayNightDetector {
+    fn scheduled_daytime(&self, clock_time: i32) -> bool {
+        (clock_time.max(0) - self.drift).div_euclid(CYCLE_SEC) % 2 == 0
+    }
+
+    /// Clock time of the first scheduled flip after given clock time
+    fn next_flip(&self, clock_time: i32) -> i32 {
+        ((clock_time.max(0) - self.drift).div_euclid(CYCLE_SEC) + 1) * CYCLE_SEC + self.drift
+    }
+
+    /// Corrects the schedule by the daytime flip observed at given clock time
+    fn observe(&mut self, clock_time: i32, daytime: bool, forced_night: bool) {
+        let flipped = self.daytime.map_or(false, |last| last != daytime);
+        self.daytime = Some(daytime);
+        if !flipped || forced_night {
+            return;
+        }
+
+        let nearest = (clock_time - self.drift + CYCLE_SEC / 2).div_euclid(CYCLE_SEC) * CYCLE_SEC
+            + self.drift;
+        let drift = clock_time - nearest;
+        if drift != 0 && drift.abs() <= MAX_DRIFT_SEC {
+            self.drift = (self.drift + drift).rem_euclid(CYCLE_SEC * 2);
+        }
+    }
+}
+
+impl Detector for DayNightDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        let (old, new) = match event {
+            GameEvent::ClockTick { old, new } => (*old, *new),
+            _ => return,
+        };
+
+        let map = match ctx.game.map.as_ref() {
+            Some(map) if ctx.game.in_game() => map,
+            _ => return,
+        };
+        let forced_night = map.nightstalker_night.unwrap_or_default();
+        if let Some(daytime) = map.daytime {
+            self.observe(new, daytime, forced_night);
+        }
+
+        // trust the flags whenever they disagree with the schedule (e.g. night forced by an ultimate)
+        let scheduled_daytime = self.scheduled_daytime(new);
+        if forced_night || map.daytime.map_or(false, |d| d != scheduled_daytime) {
+            return;
+        }
+
+        let (notify, phase) = if self.scheduled_daytime(old) {
+            (&ctx.profile.day_night.night.notify, "night")
+        } else {
+            (&ctx.profile.day_night.day.notify, "day")
+        };
+
+        let flip = self.next_flip(old);
+        let crossed = |before_sec: u16| {
+            let at = flip - before_sec as i32;
+            old < at && at <= new
+        };
+        for before_sec in notify.trigger_leads(ctx.player, crossed) {
+            println!("{} {} starts in {} sec", new, phase, before_sec);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detector::day_night::DayNightDetector;
+
+    #[test]
+    fn schedule() {
+        let detector = DayNightDetector::default();
+        assert_eq!(detector.scheduled_daytime(-30), true);
+        assert_eq!(detector.scheduled_daytime(299), true);
+        assert_eq!(detector.scheduled_daytime(300), false);
+        assert_eq!(detector.scheduled_daytime(600), true);
+        assert_eq!(detector.next_flip(-30), 300);
+        assert_eq!(detector.next_flip(300), 600);
+    }
+
+    #[test]
+    fn observed_flips_correct_schedule() {
+        let mut detector = DayNightDetector::default();
+        detector.observe(290, true, false);
+        detector.observe(302, false, false);
+        assert_eq!(detector.next_flip(310), 602);
+        assert_eq!(detector.scheduled_daytime(301), true);
+
+        // flip far from the schedule is an override
+        detector.observe(400, true, false);
+        assert_eq!(detector.next_flip(410), 602);
+
+        // forced night does not move the schedule
+        detector.observe(410, false, true);
+        assert_eq!(detector.next_flip(410), 602);
+    }
+}
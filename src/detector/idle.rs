@@ -0,0 +1,196 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+use crate::game::{Game, GAME_STATE_IN_PROGRESS, ITEM_HAND_OF_MIDAS};
+use crate::settings::{IdleConfig, Profile};
+
+/// Tracks how long a condition holds, notifies after a delay and then repeatedly
+#[derive(Default)]
+struct Idle {
+    /// Clock time since the condition holds
+    since: Option<i32>,
+    /// Clock time of the last notification
+    notified_at: Option<i32>,
+}
+
+impl Idle {
+    /// Whether to notify about the condition at given clock time
+    fn update(&mut self, holds: bool, clock_time: i32, config: &IdleConfig) -> bool {
+        if !holds {
+            *self = Idle::default();
+            return false;
+        }
+
+        let since = *self.since.get_or_insert(clock_time);
+        if clock_time - since < config.after_sec as i32 {
+            return false;
+        }
+
+        let notify = match self.notified_at {
+            None => true,
+            Some(at) => config.repeat_sec > 0 && clock_time - at >= config.repeat_sec as i32,
+        };
+        if notify {
+            self.notified_at = Some(clock_time);
+        }
+        notify
+    }
+}
+
+/// Reminds the player of a game condition (e.g. unused Hand of Midas) holding for too long
+pub struct IdleDetector {
+    name: &'static str,
+    condition: fn(&Game) -> bool,
+    config: fn(&Profile) -> &IdleConfig,
+    idle: Idle,
+}
+
+impl IdleDetector {
+    pub fn new(
+        name: &'static str,
+        condition: fn(&Game) -> bool,
+        config: fn(&Profile) -> &IdleConfig,
+    ) -> Self {
+        IdleDetector {
+            name,
+            condition,
+            config,
+            idle: Idle::default(),
+        }
+    }
+
+    pub fn midas() -> Self {
+        Self::new(
+            "hand of midas is ready to use",
+            |game| {
+                let alive = game.hero.as_ref().and_then(|h| h.alive) == Some(true);
+                let midas = game.items.as_ref().and_then(|i| i.find(ITEM_HAND_OF_MIDAS));
+                let ready = midas.map_or(false, |(_, item)| {
+                    item.can_cast == Some(true) && item.cooldown == Some(0)
+                });
+                alive && ready
+            },
+            |profile| &profile.midas,
+        )
+    }
+
+    pub fn missing_tp() -> Self {
+        Self::new(
+            "there is no teleport scroll",
+            |game| {
+                let alive = game.hero.as_ref().and_then(|h| h.alive) == Some(true);
+                let in_progress = game.game_state() == Some(GAME_STATE_IN_PROGRESS);
+                let missing = game.items.as_ref().map_or(false, |items| {
+                    items
+                        .teleport0
+                        .as_ref()
+                        .map_or(true, |tp| tp.is_empty() || tp.charges == Some(0))
+                });
+                alive && in_progress && missing
+            },
+            |profile| &profile.missing_tp,
+        )
+    }
+
+    pub fn unspent_talent() -> Self {
+        Self::new(
+            "there is an unspent talent",
+            |game| {
+                game.hero
+                    .as_ref()
+                    .map_or(false, |h| h.unspent_talents() > 0)
+            },
+            |profile| &profile.talents.unspent,
+        )
+    }
+}
+
+impl Detector for IdleDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        let clock_time = match event {
+            GameEvent::ClockTick { new, .. } => *new,
+            _ => return,
+        };
+
+        let config = (self.config)(ctx.profile);
+        let holds = ctx.game.in_game() && (self.condition)(ctx.game);
+        if self.idle.update(holds, clock_time, config) && config.notify.enabled {
+            println!("{} {}", clock_time, self.name);
+            config.notify.action.trigger(ctx.player);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detector::idle::{Idle, IdleDetector};
+    use crate::game::Game;
+    use crate::settings::{IdleConfig, NotifyAction, NotifyInfo};
+
+    fn config(repeat_sec: u16) -> IdleConfig {
+        IdleConfig {
+            notify: NotifyInfo::new(true, 0, NotifyAction::default()),
+            after_sec: 10,
+            repeat_sec,
+        }
+    }
+
+    #[test]
+    fn repeats_until_condition_ends() {
+        let config = config(30);
+        let mut idle = Idle::default();
+
+        assert_eq!(idle.update(true, 100, &config), false);
+        assert_eq!(idle.update(true, 109, &config), false);
+        assert_eq!(idle.update(true, 110, &config), true);
+        assert_eq!(idle.update(true, 111, &config), false);
+        assert_eq!(idle.update(true, 140, &config), true);
+
+        assert_eq!(idle.update(false, 141, &config), false);
+        assert_eq!(idle.update(true, 150, &config), false);
+        assert_eq!(idle.update(true, 160, &config), true);
+    }
+
+    #[test]
+    fn notifies_once_without_repeat() {
+        let config = config(0);
+        let mut idle = Idle::default();
+
+        assert_eq!(idle.update(true, 0, &config), false);
+        assert_eq!(idle.update(true, 10, &config), true);
+        assert_eq!(idle.update(true, 100, &config), false);
+    }
+
+    fn missing_tp(game_state: &str, alive: bool, teleport0: &str) -> bool {
+        let game: Game = serde_json::from_str(&format!(
+            r#"{{
+                "map": {{"game_state": "{}"}},
+                "hero": {{"alive": {}}},
+                "items": {{"slot0": {{"name": "item_branches"}}, "teleport0": {}}}
+            }}"#,
+            game_state, alive, teleport0
+        ))
+        .unwrap();
+        (IdleDetector::missing_tp().condition)(&game)
+    }
+
+    #[test]
+    fn missing_tp_condition() {
+        let in_progress = "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS";
+        let tp = r#"{"name": "item_tpscroll", "charges": 1}"#;
+        let empty = r#"{"name": "empty"}"#;
+        let no_charges = r#"{"name": "item_tpscroll", "charges": 0}"#;
+
+        assert_eq!(missing_tp(in_progress, true, tp), false);
+        assert_eq!(missing_tp(in_progress, true, empty), true);
+        assert_eq!(missing_tp(in_progress, true, no_charges), true);
+        assert_eq!(missing_tp(in_progress, true, "null"), true);
+
+        // dead hero can't use it anyway
+        assert_eq!(missing_tp(in_progress, false, empty), false);
+        // no reminder while walking to the lane
+        assert_eq!(
+            missing_tp("DOTA_GAMERULES_STATE_PRE_GAME", true, empty),
+            false
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+use crate::game::{Game, Item};
+
+/// Announces selected items coming off cooldown
+///
+/// Items moved between slots (or into the stash) produce no cooldown change
+/// of the same item in the same slot, so they stay silent
+pub struct ItemDetector;
+
+impl ItemDetector {
+    /// Item with changed cooldown and its previous cooldown
+    fn cooldown_change<'a>(event: &GameEvent, game: &'a Game) -> Option<(&'a Item, u32)> {
+        match event {
+            GameEvent::ItemCooldownChanged { slot, old, .. } => {
+                Some((game.items.as_ref()?.slot(slot)?, *old))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Detector for ItemDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        if !ctx.game.in_game() {
+            return;
+        }
+
+        let (item, old) = match Self::cooldown_change(event, ctx.game) {
+            Some(change) => change,
+            None => return,
+        };
+
+        for config in &ctx.profile.items {
+            let notified = config.on_cooldown(item, old, ctx.player);
+            for before_sec in &notified {
+                println!(
+                    "{} item {} is ready in {} sec",
+                    ctx.clock_time(),
+                    config.item,
+                    before_sec
+                );
+            }
+            if !notified.is_empty() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::delta::GameDelta;
+    use crate::detector::item::ItemDetector;
+    use crate::game::Game;
+
+    fn cooldown_changes(json: &str) -> Vec<(String, u32, u32)> {
+        let game: Game = serde_json::from_str(json).unwrap();
+        GameDelta::new(&game)
+            .events
+            .iter()
+            .filter_map(|event| ItemDetector::cooldown_change(event, &game))
+            .map(|(item, old)| {
+                let name = item.name.clone().unwrap_or_default();
+                (name, old, item.cooldown.unwrap_or_default())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cooldown_in_same_slot() {
+        let changes = cooldown_changes(
+            r#"{
+                "items": {"slot0": {"name": "item_black_king_bar", "cooldown": 5}},
+                "previously": {"items": {"slot0": {"cooldown": 6}}}
+            }"#,
+        );
+        assert_eq!(changes, vec![("item_black_king_bar".to_string(), 6, 5)]);
+    }
+
+    #[test]
+    fn moved_item_is_silent() {
+        // between the slots
+        let changes = cooldown_changes(
+            r#"{
+                "items": {
+                    "slot0": {"name": "empty"},
+                    "slot1": {"name": "item_black_king_bar", "cooldown": 5}
+                },
+                "previously": {"items": {
+                    "slot0": {"name": "item_black_king_bar", "cooldown": 6},
+                    "slot1": {"name": "empty", "cooldown": 0}
+                }}
+            }"#,
+        );
+        assert_eq!(changes, vec![]);
+
+        // into the stash
+        let changes = cooldown_changes(
+            r#"{
+                "items": {
+                    "slot0": {"name": "empty"},
+                    "stash0": {"name": "item_black_king_bar", "cooldown": 5}
+                },
+                "previously": {"items": {
+                    "slot0": {"name": "item_black_king_bar", "cooldown": 6},
+                    "stash0": {"name": "empty", "cooldown": 0}
+                }}
+            }"#,
+        );
+        assert_eq!(changes, vec![]);
+    }
+}
@@ -0,0 +1,27 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+
+pub struct LifecycleDetector;
+
+impl Detector for LifecycleDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        match event {
+            GameEvent::MatchStateChanged { old, new } => println!("{:?} -> {:?}", old, new),
+            GameEvent::MatchStarted { match_id } => {
+                println!("match {} started", match_id);
+                let notify = &ctx.profile.match_started.notify;
+                if notify.enabled {
+                    notify.action.trigger(ctx.player);
+                }
+            }
+            GameEvent::MatchEnded { match_id } => {
+                println!("match {} ended", match_id);
+                let notify = &ctx.profile.match_ended.notify;
+                if notify.enabled {
+                    notify.action.trigger(ctx.player);
+                }
+            }
+            _ => {}
+        }
+    }
+}
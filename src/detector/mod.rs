@@ -0,0 +1,93 @@
+use crate::audio::AudioPlayer;
+use crate::delta::{GameDelta, GameEvent};
+use crate::game::Game;
+use crate::settings::Profile;
+
+mod ability;
+mod aegis;
+mod buyback;
+mod day_night;
+mod idle;
+mod item;
+mod lifecycle;
+mod respawn;
+mod roshan;
+mod spawn;
+mod spectator;
+mod talent;
+mod threshold;
+mod ward;
+
+use ability::AbilityDetector;
+use aegis::AegisDetector;
+use buyback::BuybackDetector;
+use day_night::DayNightDetector;
+use idle::IdleDetector;
+use item::ItemDetector;
+use lifecycle::LifecycleDetector;
+use respawn::RespawnDetector;
+use roshan::RoshanDetector;
+use spawn::SpawnDetector;
+use spectator::SpectatorDetector;
+use talent::TalentDetector;
+use threshold::ThresholdDetector;
+use ward::ObserverWardDetector;
+
+/// Everything detector needs to react on a game event
+pub struct Context<'a> {
+    pub game: &'a Game,
+    pub profile: &'a Profile,
+    pub player: &'a AudioPlayer,
+}
+
+impl Context<'_> {
+    /// Clock time used for logging
+    pub fn clock_time(&self) -> i32 {
+        self.game.clock_time().unwrap_or_default()
+    }
+}
+
+pub trait Detector: Send {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context);
+}
+
+pub struct Detectors(Vec<Box<dyn Detector>>);
+
+impl Detectors {
+    /// Drops state of all detectors (e.g. when new match starts)
+    pub fn reset(&mut self) {
+        *self = Detectors::default();
+    }
+
+    pub fn handle(&mut self, delta: &GameDelta, ctx: &Context) {
+        for event in &delta.events {
+            for detector in self.0.iter_mut() {
+                detector.on_event(event, ctx);
+            }
+        }
+    }
+}
+
+impl Default for Detectors {
+    fn default() -> Self {
+        Detectors(vec![
+            Box::new(LifecycleDetector),
+            Box::new(BuybackDetector::default()),
+            Box::new(RespawnDetector::default()),
+            Box::new(ThresholdDetector::low_health()),
+            Box::new(ThresholdDetector::low_mana()),
+            Box::new(ObserverWardDetector),
+            Box::new(AbilityDetector),
+            Box::new(ItemDetector),
+            Box::new(IdleDetector::midas()),
+            Box::new(IdleDetector::missing_tp()),
+            Box::new(TalentDetector),
+            Box::new(IdleDetector::unspent_talent()),
+            Box::new(SpawnDetector::default()),
+            Box::new(RoshanDetector::default()),
+            Box::new(AegisDetector::default()),
+            Box::new(DayNightDetector::default()),
+            Box::new(SpectatorDetector::default()),
+        ])
+    }
+}
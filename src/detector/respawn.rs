@@ -0,0 +1,115 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+use crate::game::Game;
+use crate::settings::RespawnConfig;
+
+/// Respawn countdown & buyback cue of the own hero
+#[derive(Default)]
+pub struct RespawnDetector {
+    /// Last seen respawn seconds while the hero is dead
+    respawn_seconds: Option<u32>,
+}
+
+/// Respawn countdown passed `before_sec` since the last update
+fn countdown_crossed(last_respawn_seconds: u32, respawn_seconds: u32, before_sec: u16) -> bool {
+    last_respawn_seconds > before_sec as u32 && respawn_seconds <= before_sec as u32
+}
+
+/// Hero died and can buy back right away
+fn buyback_cue(config: &RespawnConfig, game: &Game) -> bool {
+    config.buyback.notify.enabled && game.has_buyback()
+}
+
+impl Detector for RespawnDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        if !ctx.game.in_game() {
+            return;
+        }
+
+        let config = &ctx.profile.respawn;
+        match event {
+            GameEvent::HeroDied { respawn_seconds } => {
+                self.respawn_seconds = Some(*respawn_seconds);
+
+                if buyback_cue(config, ctx.game) {
+                    println!("{} hero died, buyback available", ctx.clock_time());
+                    config.buyback.notify.action.trigger(ctx.player);
+                }
+            }
+            GameEvent::HeroRespawned => self.respawn_seconds = None,
+            GameEvent::ClockTick { .. } => {
+                let last_respawn_seconds = match self.respawn_seconds {
+                    Some(last) => last,
+                    None => return,
+                };
+                let respawn_seconds = match ctx.game.hero.as_ref() {
+                    Some(hero) if hero.alive == Some(false) => {
+                        hero.respawn_seconds.unwrap_or_default()
+                    }
+                    _ => return,
+                };
+                self.respawn_seconds = Some(respawn_seconds);
+
+                let crossed = |before_sec| {
+                    countdown_crossed(last_respawn_seconds, respawn_seconds, before_sec)
+                };
+                for before_sec in config.countdown.notify.trigger_leads(ctx.player, crossed) {
+                    println!("{} hero respawns in {} sec", ctx.clock_time(), before_sec);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detector::respawn::{buyback_cue, countdown_crossed};
+    use crate::game::Game;
+    use crate::settings::Profile;
+
+    #[test]
+    fn countdown() {
+        let config = Profile::default().respawn;
+        let crossed = |last: u32, now: u32| -> Vec<u16> {
+            config
+                .countdown
+                .notify
+                .leads()
+                .map(|(before_sec, _)| before_sec)
+                .filter(|before_sec| countdown_crossed(last, now, *before_sec))
+                .collect()
+        };
+
+        assert_eq!(crossed(12, 11), Vec::<u16>::new());
+        assert_eq!(crossed(11, 10), vec![10]);
+        assert_eq!(crossed(10, 9), Vec::<u16>::new());
+        assert_eq!(crossed(6, 5), vec![5]);
+        assert_eq!(crossed(5, 4), Vec::<u16>::new());
+
+        // skipped updates
+        assert_eq!(crossed(12, 3), vec![10, 5]);
+    }
+
+    fn game(gold_reliable: u32, buyback_cooldown: u32) -> Game {
+        serde_json::from_str(&format!(
+            r#"{{
+                "player": {{"gold_reliable": {}}},
+                "hero": {{"alive": false, "buyback_cost": 1000, "buyback_cooldown": {}}}
+            }}"#,
+            gold_reliable, buyback_cooldown
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn buyback() {
+        let mut config = Profile::default().respawn;
+        assert_eq!(buyback_cue(&config, &game(1500, 0)), false);
+
+        config.buyback.notify.enabled = true;
+        assert_eq!(buyback_cue(&config, &game(1500, 0)), true);
+        assert_eq!(buyback_cue(&config, &game(900, 0)), false);
+        assert_eq!(buyback_cue(&config, &game(1500, 240)), false);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+use crate::game::{Game, EVENT_ROSHAN_KILLED};
+
+/// Roshan state once killed (reported by newer game versions)
+const ROSHAN_STATE_RESPAWN_BASE: &'static str = "respawn_base";
+
+/// Announces roshan respawn window based on `roshan_killed` events or roshan state
+#[derive(Default)]
+pub struct RoshanDetector {
+    /// Clock time of the last roshan kill
+    killed_at: Option<i32>,
+    roshan_state: Option<String>,
+}
+
+/// Clock time `before_sec` ahead of `at` passed since the last clock tick
+fn crossed(at: i32, before_sec: u16, old: i32, new: i32) -> bool {
+    let at = at - before_sec as i32;
+    old < at && at <= new
+}
+
+impl RoshanDetector {
+    fn update_killed_at(&mut self, clock_time: i32, game: &Game) {
+        let last_kill = game.event_clock_times(EVENT_ROSHAN_KILLED).max();
+
+        let map = game.map.as_ref();
+        let roshan_state = map.and_then(|m| m.roshan_state.clone());
+        let state_kill = match (&self.roshan_state, &roshan_state) {
+            (Some(old), Some(new)) if old != new && new == ROSHAN_STATE_RESPAWN_BASE => {
+                Some(clock_time)
+            }
+            _ => None,
+        };
+        self.roshan_state = roshan_state;
+
+        if let Some(killed_at) = last_kill.or(state_kill) {
+            if self.killed_at.map_or(true, |last| killed_at > last) {
+                println!("{} roshan killed at {}", clock_time, killed_at);
+                self.killed_at = Some(killed_at);
+            }
+        }
+    }
+}
+
+impl Detector for RoshanDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        let (old, new) = match event {
+            GameEvent::ClockTick { old, new } => (*old, *new),
+            _ => return,
+        };
+
+        if !ctx.game.in_game() {
+            return;
+        }
+
+        self.update_killed_at(new, ctx.game);
+
+        let config = &ctx.profile.roshan;
+        let killed_at = match self.killed_at {
+            Some(killed_at) => killed_at,
+            None => return,
+        };
+
+        let (min_at, max_at) = config.respawn_times(killed_at);
+
+        let notify = &config.min_respawn.notify;
+        for before_sec in notify.trigger_leads(ctx.player, |b| crossed(min_at, b, old, new)) {
+            println!("{} roshan respawn window starts in {} sec", new, before_sec);
+        }
+
+        let notify = &config.max_respawn.notify;
+        for before_sec in notify.trigger_leads(ctx.player, |b| crossed(max_at, b, old, new)) {
+            println!("{} roshan respawn window ends in {} sec", new, before_sec);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detector::roshan::{crossed, RoshanDetector};
+    use crate::game::Game;
+    use crate::settings::Profile;
+
+    fn game(json: &str) -> Game {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn killed_event() {
+        let mut detector = RoshanDetector::default();
+        detector.update_killed_at(
+            1000,
+            &game(r#"{"map": {"clock_time": 1000, "game_time": 1090}}"#),
+        );
+        assert_eq!(detector.killed_at, None);
+
+        let killed = game(
+            r#"{
+                "map": {"clock_time": 1000, "game_time": 1090},
+                "events": [{"game_time": 1085, "event_type": "roshan_killed"}]
+            }"#,
+        );
+        detector.update_killed_at(1000, &killed);
+        assert_eq!(detector.killed_at, Some(995));
+
+        // the same event repeated in following updates
+        detector.update_killed_at(1001, &killed);
+        assert_eq!(detector.killed_at, Some(995));
+    }
+
+    #[test]
+    fn killed_state() {
+        let mut detector = RoshanDetector::default();
+        let alive = game(r#"{"map": {"roshan_state": "alive"}}"#);
+        let dead = game(r#"{"map": {"roshan_state": "respawn_base"}}"#);
+
+        // state seen for the first time is not a kill
+        detector.update_killed_at(1000, &dead);
+        assert_eq!(detector.killed_at, None);
+
+        detector.update_killed_at(1001, &alive);
+        detector.update_killed_at(1500, &dead);
+        assert_eq!(detector.killed_at, Some(1500));
+        detector.update_killed_at(1501, &dead);
+        assert_eq!(detector.killed_at, Some(1500));
+    }
+
+    #[test]
+    fn respawn_window() {
+        let config = Profile::default().roshan;
+        let (min_at, max_at) = config.respawn_times(1000);
+        assert_eq!((min_at, max_at), (1000 + 8 * 60, 1000 + 11 * 60));
+
+        assert_eq!(crossed(min_at, 0, 1479, 1480), true);
+        assert_eq!(crossed(min_at, 0, 1480, 1481), false);
+        assert_eq!(crossed(min_at, 30, 1449, 1450), true);
+        assert_eq!(crossed(max_at, 0, 1650, 1665), true);
+        assert_eq!(crossed(max_at, 0, 1660, 1665), false);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+use crate::settings::{OnClock, TimerConfig};
+use std::collections::HashMap;
+
+/// Larger clock jump is a reconnect (or replay seek), spawns crossed by it are not announced
+const MAX_CATCH_UP_SEC: i32 = 30;
+
+/// Announces spawns of all timers, each one tracks the last processed clock time
+/// so skipped seconds are caught up and repeated ones do not fire twice
+#[derive(Default)]
+pub struct SpawnDetector {
+    /// Last processed clock time keyed by timer
+    last_clock: HashMap<String, i32>,
+}
+
+impl SpawnDetector {
+    /// Clock time interval (last, now] not yet processed by the timer
+    fn interval(&mut self, timer: &str, old: i32, now: i32) -> Option<(i32, i32)> {
+        let last = self.last_clock.get(timer).copied().unwrap_or(old);
+
+        // stale (or repeated) tick must not rewind the clock time already processed
+        if now <= last && last - now <= MAX_CATCH_UP_SEC {
+            return None;
+        }
+        self.last_clock.insert(timer.to_string(), now);
+
+        if now <= last || now - last > MAX_CATCH_UP_SEC {
+            return None;
+        }
+        Some((last, now))
+    }
+
+    /// Custom timers with their intervals, keyed by position as the user given ids may repeat
+    fn timer_intervals<'a>(
+        &mut self,
+        timers: &'a [TimerConfig],
+        old: i32,
+        now: i32,
+    ) -> Vec<(&'a TimerConfig, i32, i32)> {
+        timers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, timer)| {
+                let (last, now) = self.interval(&format!("timer/{}", index), old, now)?;
+                Some((timer, last, now))
+            })
+            .collect()
+    }
+}
+
+impl Detector for SpawnDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        let (old, new) = match event {
+            GameEvent::ClockTick { old, new } => (*old, *new),
+            _ => return,
+        };
+
+        if !ctx.game.in_game() {
+            return;
+        }
+
+        let s = ctx.profile;
+
+        if let Some((last, now)) = self.interval("bounty_rune", old, new) {
+            for before_sec in s.bounty_rune.on_clock(last, now, ctx.player) {
+                println!(
+                    "{} there are bounty runes about to spawn in {} sec",
+                    now, before_sec
+                );
+            }
+        }
+
+        if let Some((last, now)) = self.interval("power_rune", old, new) {
+            for before_sec in s.power_rune.on_clock(last, now, ctx.player) {
+                println!(
+                    "{} there are power runes about to spawn in {} sec",
+                    now, before_sec
+                );
+            }
+        }
+
+        if let Some((last, now)) = self.interval("tomb_of_knowledge", old, new) {
+            for before_sec in s.tomb_of_knowledge.on_clock(last, now, ctx.player) {
+                println!(
+                    "{} there is tomb of knowledge about to spawn in {} sec",
+                    now, before_sec
+                );
+            }
+        }
+
+        if let Some((last, now)) = self.interval("neutral_items", old, new) {
+            for (tier, before_sec) in s.neutral_items.on_clock(last, now, ctx.player) {
+                println!(
+                    "{} there are tier {} neutral items that can be dropped in about {} sec",
+                    now, tier, before_sec
+                );
+            }
+        }
+
+        for (timer, last, now) in self.timer_intervals(&s.timers, old, new) {
+            for before_sec in timer.on_clock(last, now, ctx.player) {
+                println!("{} timer {} in {} sec", now, timer.label, before_sec);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detector::spawn::SpawnDetector;
+    use crate::settings::TimerConfig;
+
+    #[test]
+    fn interval_per_timer() {
+        let mut detector = SpawnDetector::default();
+        assert_eq!(detector.interval("rune", 103, 104), Some((103, 104)));
+        assert_eq!(detector.interval("rune", 103, 106), Some((104, 106)));
+        assert_eq!(detector.interval("rune", 105, 106), None);
+
+        // timer added later starts with the current tick
+        assert_eq!(detector.interval("tomb", 105, 107), Some((105, 107)));
+
+        // reconnect
+        assert_eq!(detector.interval("rune", 106, 600), None);
+        assert_eq!(detector.interval("rune", 600, 601), Some((600, 601)));
+    }
+
+    #[test]
+    fn backwards_tick() {
+        let mut detector = SpawnDetector::default();
+        assert_eq!(detector.interval("rune", 100, 105), Some((100, 105)));
+
+        // out of order update does not fire (101, 105] again
+        assert_eq!(detector.interval("rune", 100, 101), None);
+        assert_eq!(detector.interval("rune", 101, 104), None);
+        assert_eq!(detector.interval("rune", 104, 106), Some((105, 106)));
+
+        // replay seek far back starts over
+        assert_eq!(detector.interval("rune", 106, 10), None);
+        assert_eq!(detector.interval("rune", 10, 11), Some((10, 11)));
+    }
+
+    #[test]
+    fn timers_with_same_id() {
+        let timers: Vec<TimerConfig> = serde_json::from_str(
+            r#"[
+                {"id": "t", "label": "Stack", "notify": {"enabled": true, "before_sec": 10, "action": {"type": "beep", "duration_ms": 100, "freq": 400}}, "spawn": {"first_sec": 60, "interval_sec": 60}},
+                {"id": "t", "label": "Pull", "notify": {"enabled": true, "before_sec": 5, "action": {"type": "beep", "duration_ms": 100, "freq": 400}}, "spawn": {"first_sec": 45, "interval_sec": 60}}
+            ]"#,
+        )
+        .unwrap();
+
+        let mut detector = SpawnDetector::default();
+        let labels = |intervals: Vec<(&TimerConfig, i32, i32)>| -> Vec<(String, i32, i32)> {
+            intervals
+                .into_iter()
+                .map(|(timer, last, now)| (timer.label.clone(), last, now))
+                .collect()
+        };
+        assert_eq!(
+            labels(detector.timer_intervals(&timers, 39, 40)),
+            vec![("Stack".to_string(), 39, 40), ("Pull".to_string(), 39, 40)]
+        );
+        assert_eq!(
+            labels(detector.timer_intervals(&timers, 40, 41)),
+            vec![("Stack".to_string(), 40, 41), ("Pull".to_string(), 40, 41)]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+use crate::game::{Spectated, TEAM_RADIANT};
+use std::collections::HashMap;
+
+/// Buyback, respawn & net worth announcements for both teams when spectating
+#[derive(Default)]
+pub struct SpectatorDetector {
+    /// Buyback availability keyed by player slot
+    buyback: HashMap<String, bool>,
+    /// Last seen respawn seconds keyed by player slot
+    respawn: HashMap<String, u32>,
+    /// Net worth lead (radiant - dire) at the last announcement
+    net_worth_lead: Option<i64>,
+}
+
+impl SpectatorDetector {
+    /// Returns the lead of the last announcement when the lead changed at least by `swing`
+    fn net_worth_swing(&mut self, lead: i64, swing: u32) -> Option<i64> {
+        let last_lead = *self.net_worth_lead.get_or_insert(lead);
+        if (lead - last_lead).abs() >= swing as i64 {
+            self.net_worth_lead = Some(lead);
+            Some(last_lead)
+        } else {
+            None
+        }
+    }
+
+    fn on_buyback(&mut self, spectated: &Spectated, ctx: &Context) {
+        let notify = &ctx.profile.spectator.buyback.notify;
+        let hero = match spectated.hero {
+            Some(hero) => hero,
+            None => return,
+        };
+
+        let has_buyback = spectated.has_buyback();
+
+        let last_buyback = self
+            .buyback
+            .insert(spectated.slot.to_string(), has_buyback)
+            .unwrap_or(has_buyback);
+        if notify.enabled && has_buyback && !last_buyback {
+            println!(
+                "{} {} has buyback available",
+                ctx.clock_time(),
+                hero.name.as_deref().unwrap_or(spectated.slot)
+            );
+            notify.action.trigger(ctx.player);
+        }
+    }
+
+    fn on_respawn(&mut self, spectated: &Spectated, ctx: &Context) {
+        let notify = &ctx.profile.spectator.respawn.notify;
+        let hero = match spectated.hero {
+            Some(hero) if hero.alive == Some(false) => hero,
+            _ => {
+                self.respawn.remove(spectated.slot);
+                return;
+            }
+        };
+
+        let respawn_seconds = hero.respawn_seconds.unwrap_or_default();
+        let last_respawn_seconds = self
+            .respawn
+            .insert(spectated.slot.to_string(), respawn_seconds);
+
+        let crossed = |before_sec: u16| {
+            last_respawn_seconds.map_or(false, |last| last > before_sec as u32)
+                && respawn_seconds <= before_sec as u32
+        };
+        for before_sec in notify.trigger_leads(ctx.player, crossed) {
+            println!(
+                "{} {} respawns in {} sec",
+                ctx.clock_time(),
+                hero.name.as_deref().unwrap_or(spectated.slot),
+                before_sec
+            );
+        }
+    }
+
+    fn on_net_worth(&mut self, spectated: &[Spectated], ctx: &Context) {
+        let config = &ctx.profile.spectator.net_worth;
+        let lead: i64 = spectated
+            .iter()
+            .map(|s| {
+                let net_worth = s.player.and_then(|p| p.net_worth).unwrap_or_default() as i64;
+                if s.team == TEAM_RADIANT {
+                    net_worth
+                } else {
+                    -net_worth
+                }
+            })
+            .sum();
+
+        if let Some(last_lead) = self.net_worth_swing(lead, config.swing) {
+            if config.notify.enabled {
+                println!(
+                    "{} net worth lead swung from {} to {}",
+                    ctx.clock_time(),
+                    last_lead,
+                    lead
+                );
+                config.notify.action.trigger(ctx.player);
+            }
+        }
+    }
+}
+
+impl Detector for SpectatorDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        match event {
+            GameEvent::ClockTick { .. } => {}
+            _ => return,
+        }
+
+        if !ctx.game.in_game() || !ctx.game.is_spectating() {
+            return;
+        }
+
+        let spectated = ctx.game.spectated();
+        for s in &spectated {
+            self.on_buyback(s, ctx);
+            self.on_respawn(s, ctx);
+        }
+        self.on_net_worth(&spectated, ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detector::spectator::SpectatorDetector;
+
+    #[test]
+    fn net_worth_swing() {
+        let mut detector = SpectatorDetector::default();
+        assert_eq!(detector.net_worth_swing(1000, 5000), None);
+        assert_eq!(detector.net_worth_swing(5999, 5000), None);
+        assert_eq!(detector.net_worth_swing(6000, 5000), Some(1000));
+
+        // measured from the last announcement, not the last update
+        assert_eq!(detector.net_worth_swing(2000, 5000), None);
+        assert_eq!(detector.net_worth_swing(1001, 5000), None);
+        assert_eq!(detector.net_worth_swing(1000, 5000), Some(6000));
+        assert_eq!(detector.net_worth_swing(-4000, 5000), Some(1000));
+    }
+}
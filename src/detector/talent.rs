@@ -0,0 +1,97 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+use crate::game::{MAX_LEVEL, TALENT_LEVELS};
+
+/// Aghanim's Shard can be bought from 15:00 clock time
+const SHARD_AVAILABLE_SEC: i32 = 15 * 60;
+
+/// Level up reached level with a new talent or the max level
+fn milestone(old: u8, new: u8) -> bool {
+    TALENT_LEVELS
+        .iter()
+        .chain(Some(&MAX_LEVEL))
+        .any(|level| old < *level && *level <= new)
+}
+
+/// Shard availability `before_sec` ahead passed since the last clock tick
+fn shard_crossed(old: i32, new: i32, before_sec: u16) -> bool {
+    let at = SHARD_AVAILABLE_SEC - before_sec as i32;
+    old < at && at <= new
+}
+
+/// Announces talent levels, max level & Aghanim's Shard availability
+pub struct TalentDetector;
+
+impl Detector for TalentDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        if !ctx.game.in_game() {
+            return;
+        }
+
+        let config = &ctx.profile.talents;
+        match event {
+            GameEvent::LevelUp { old, new } => {
+                let notify = &config.level.notify;
+                if notify.enabled && milestone(*old, *new) {
+                    println!("{} hero reached level {}", ctx.clock_time(), new);
+                    notify.action.trigger(ctx.player);
+                }
+            }
+            GameEvent::ClockTick { old, new } => {
+                let crossed = |before_sec| shard_crossed(*old, *new, before_sec);
+                for before_sec in config.shard.notify.trigger_leads(ctx.player, crossed) {
+                    println!("{} aghanim's shard is available in {} sec", new, before_sec);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detector::talent::{milestone, shard_crossed};
+    use crate::game::Hero;
+
+    #[test]
+    fn talent_levels() {
+        assert_eq!(milestone(9, 10), true);
+        assert_eq!(milestone(10, 11), false);
+        assert_eq!(milestone(24, 25), true);
+        assert_eq!(milestone(29, 30), true);
+        assert_eq!(milestone(26, 27), false);
+
+        // several levels at once (e.g. tome of knowledge)
+        assert_eq!(milestone(8, 11), true);
+    }
+
+    #[test]
+    fn unspent_talents() {
+        let hero = |json: &str| -> Hero { serde_json::from_str(json).unwrap() };
+        assert_eq!(hero(r#"{"level": 9}"#).unspent_talents(), 0);
+        assert_eq!(hero(r#"{"level": 10}"#).unspent_talents(), 1);
+        assert_eq!(
+            hero(r#"{"level": 10, "talent_2": true}"#).unspent_talents(),
+            0
+        );
+        assert_eq!(
+            hero(r#"{"level": 20, "talent_1": true, "talent_2": false}"#).unspent_talents(),
+            2
+        );
+        assert_eq!(
+            hero(r#"{"level": 30, "talent_1": true, "talent_4": true, "talent_6": true, "talent_7": true}"#)
+                .unspent_talents(),
+            0
+        );
+    }
+
+    #[test]
+    fn shard_once() {
+        assert_eq!(shard_crossed(898, 899, 0), false);
+        assert_eq!(shard_crossed(899, 900, 0), true);
+        assert_eq!(shard_crossed(900, 900, 0), false);
+        assert_eq!(shard_crossed(900, 901, 0), false);
+        assert_eq!(shard_crossed(895, 905, 0), true);
+        assert_eq!(shard_crossed(869, 870, 30), true);
+    }
+}
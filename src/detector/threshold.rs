@@ -0,0 +1,136 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+use crate::game::{Game, Hero};
+use crate::settings::{Profile, ThresholdConfig};
+
+/// Edge detection of a value dropping below the threshold, with hysteresis & cooldown
+#[derive(Default)]
+struct Threshold {
+    below: bool,
+    /// Clock time of the last notification
+    notified_at: Option<i32>,
+}
+
+impl Threshold {
+    /// Whether to notify about the value at given clock time
+    fn update(&mut self, value: u32, clock_time: i32, config: &ThresholdConfig) -> bool {
+        if self.below {
+            if value >= config.threshold + config.hysteresis {
+                self.below = false;
+            }
+            return false;
+        }
+
+        if value >= config.threshold {
+            return false;
+        }
+        self.below = true;
+
+        let cooled_down = self
+            .notified_at
+            .map_or(true, |at| clock_time - at >= config.cooldown_sec as i32);
+        if cooled_down {
+            self.notified_at = Some(clock_time);
+        }
+        cooled_down
+    }
+}
+
+/// Announces any numeric game value (e.g. hero health percent) dropping below configured threshold
+pub struct ThresholdDetector {
+    name: &'static str,
+    value: fn(&Game) -> Option<u32>,
+    config: fn(&Profile) -> &ThresholdConfig,
+    threshold: Threshold,
+}
+
+/// Values of a dead hero are not worth announcing
+fn alive_hero(game: &Game) -> Option<&Hero> {
+    game.hero.as_ref().filter(|hero| hero.alive == Some(true))
+}
+
+impl ThresholdDetector {
+    pub fn new(
+        name: &'static str,
+        value: fn(&Game) -> Option<u32>,
+        config: fn(&Profile) -> &ThresholdConfig,
+    ) -> Self {
+        ThresholdDetector {
+            name,
+            value,
+            config,
+            threshold: Threshold::default(),
+        }
+    }
+
+    pub fn low_health() -> Self {
+        Self::new(
+            "health",
+            |game| alive_hero(game)?.health_percent.map(u32::from),
+            |profile| &profile.low_health,
+        )
+    }
+
+    pub fn low_mana() -> Self {
+        Self::new(
+            "mana",
+            |game| alive_hero(game)?.mana_percent.map(u32::from),
+            |profile| &profile.low_mana,
+        )
+    }
+}
+
+impl Detector for ThresholdDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        let clock_time = match event {
+            GameEvent::ClockTick { new, .. } => *new,
+            _ => return,
+        };
+
+        let config = (self.config)(ctx.profile);
+        let value = match (self.value)(ctx.game) {
+            Some(value) if ctx.game.in_game() => value,
+            _ => return,
+        };
+
+        if self.threshold.update(value, clock_time, config) && config.notify.enabled {
+            println!("{} {} is low ({})", clock_time, self.name, value);
+            config.notify.action.trigger(ctx.player);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detector::threshold::Threshold;
+    use crate::settings::{NotifyAction, NotifyInfo, ThresholdConfig};
+
+    fn config() -> ThresholdConfig {
+        ThresholdConfig {
+            notify: NotifyInfo::new(true, 0, NotifyAction::default()),
+            threshold: 25,
+            hysteresis: 10,
+            cooldown_sec: 30,
+        }
+    }
+
+    #[test]
+    fn hysteresis_and_cooldown() {
+        let config = config();
+        let mut threshold = Threshold::default();
+
+        assert_eq!(threshold.update(30, 0, &config), false);
+        assert_eq!(threshold.update(24, 1, &config), true);
+        assert_eq!(threshold.update(20, 2, &config), false);
+
+        // regeneration jitter around the threshold
+        assert_eq!(threshold.update(26, 3, &config), false);
+        assert_eq!(threshold.update(24, 4, &config), false);
+
+        // re-armed but still cooling down
+        assert_eq!(threshold.update(35, 5, &config), false);
+        assert_eq!(threshold.update(24, 6, &config), false);
+        assert_eq!(threshold.update(40, 40, &config), false);
+        assert_eq!(threshold.update(10, 41, &config), true);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::delta::GameEvent;
+use crate::detector::{Context, Detector};
+
+pub struct ObserverWardDetector;
+
+impl Detector for ObserverWardDetector {
+    fn on_event(&mut self, event: &GameEvent, ctx: &Context) {
+        let cooldown = match event {
+            GameEvent::WardCooldownChanged { new, .. } => *new,
+            _ => return,
+        };
+
+        if !ctx.game.in_game() {
+            return;
+        }
+
+        let notify = &ctx.profile.observer_wards.notify;
+        for before_sec in
+            notify.trigger_leads(ctx.player, |before_sec| cooldown == before_sec as i32)
+        {
+            println!(
+                "{} there are observer wards about to spawn in {} sec",
+                ctx.clock_time(),
+                before_sec
+            );
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::embed::Disposition::{Attachment, Inline};
+use crate::embed::Disposition::Inline;
 use rocket::handler::{Handler, Outcome};
 use rocket::http::ext::IntoOwned;
 use rocket::http::uri::Segments;
@@ -132,10 +132,6 @@ impl<T: RustEmbed> EmbedFile<T> {
         Self::new(path, None)
     }
 
-    pub fn of_attachment<P: AsRef<Path>>(path: P) -> io::Result<EmbedFile<T>> {
-        Self::new(path, Some(Attachment))
-    }
-
     pub fn of_inline<P: AsRef<Path>>(path: P) -> io::Result<EmbedFile<T>> {
         Self::new(path, Some(Inline))
     }
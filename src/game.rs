@@ -1,5 +1,7 @@
-use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
 use std::io;
 use std::io::{BufRead, BufReader};
@@ -10,30 +12,212 @@ use winreg::RegKey;
 
 pub const GAME_STATE_INTEGRATION_FILE_NAME: &'static str = "gamestate_integration_announcer.cfg";
 
+/// Placeholder in the game state integration file replaced by the auth token
+pub const AUTH_TOKEN_PLACEHOLDER: &'static str = "${AUTH_TOKEN}";
+
+pub const TEAM_RADIANT: &'static str = "team2";
+pub const TEAM_DIRE: &'static str = "team3";
+
+pub const GAME_STATE_PRE_GAME: &'static str = "DOTA_GAMERULES_STATE_PRE_GAME";
+pub const GAME_STATE_IN_PROGRESS: &'static str = "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS";
+
 #[derive(Deserialize, Debug)]
 #[serde(default)]
 pub struct Game {
+    pub auth: Option<Auth>,
     pub provider: Option<Provider>,
     pub map: Option<Map>,
-    pub player: Value,
-    pub hero: Value,
-    pub abilities: Value,
-    pub items: Value,
-    pub previously: Value,
+    pub player: Option<Player>,
+    pub hero: Option<Hero>,
+    pub abilities: Option<Abilities>,
+    pub items: Option<Items>,
+    /// Recent game events (e.g. `roshan_killed`)
+    pub events: Option<Vec<Event>>,
+    /// Players keyed by their slot, sent by some spectator clients
+    pub allplayers: Option<BTreeMap<String, Player>>,
+    pub previously: Previously,
+    /// Keys which were not present in the previous update (every leaf is `true`)
+    pub added: Value,
 }
 
 impl Default for Game {
     fn default() -> Self {
         Game {
+            auth: None,
             provider: None,
             map: None,
-            player: Value::Null,
-            hero: Value::Null,
-            abilities: Value::Null,
-            items: Value::Null,
-            previously: Value::Null,
+            player: None,
+            hero: None,
+            abilities: None,
+            items: None,
+            events: None,
+            allplayers: None,
+            previously: Previously::default(),
+            added: Value::Null,
+        }
+    }
+}
+
+impl Game {
+    pub fn clock_time(&self) -> Option<i32> {
+        self.map.as_ref().and_then(|m| m.clock_time)
+    }
+
+    pub fn game_state(&self) -> Option<&str> {
+        self.map.as_ref().and_then(|m| m.game_state.as_deref())
+    }
+
+    /// Replaces single top level section (e.g. `hero`) with its new value,
+    /// unknown sections are ignored
+    pub fn set_section(&mut self, section: &str, value: &Value) -> serde_json::Result<()> {
+        match section {
+            "auth" => self.auth = Deserialize::deserialize(value)?,
+            "provider" => self.provider = Deserialize::deserialize(value)?,
+            "map" => self.map = Deserialize::deserialize(value)?,
+            "player" => self.player = Deserialize::deserialize(value)?,
+            "hero" => self.hero = Deserialize::deserialize(value)?,
+            "abilities" => self.abilities = Deserialize::deserialize(value)?,
+            "items" => self.items = Deserialize::deserialize(value)?,
+            "events" => self.events = Deserialize::deserialize(value)?,
+            "allplayers" => self.allplayers = Deserialize::deserialize(value)?,
+            "previously" => self.previously = Deserialize::deserialize(value)?,
+            "added" => self.added = value.clone(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Pre-game or game in progress
+    pub fn in_game(&self) -> bool {
+        match self.game_state() {
+            Some(GAME_STATE_PRE_GAME) | Some(GAME_STATE_IN_PROGRESS) => true,
+            _ => false,
         }
     }
+
+    /// Player has enough reliable gold for the buyback and it's not on cooldown
+    pub fn has_buyback(&self) -> bool {
+        self.hero
+            .as_ref()
+            .map_or(false, |h| h.has_buyback(self.player.as_ref()))
+    }
+
+    /// Spectator (or caster) receives players & heroes of both teams
+    pub fn is_spectating(&self) -> bool {
+        self.player.as_ref().map_or(false, |p| p.is_team_view()) || self.allplayers.is_some()
+    }
+
+    /// Players & heroes of both teams, empty unless spectating
+    pub fn spectated(&self) -> Vec<Spectated<'_>> {
+        let mut spectated = Vec::new();
+        for team in &[TEAM_RADIANT, TEAM_DIRE] {
+            let players = self.player.as_ref().and_then(|p| p.team(team));
+            let heroes = self.hero.as_ref().and_then(|h| h.team(team));
+
+            let slots: BTreeSet<&String> = players
+                .iter()
+                .flat_map(|p| p.keys())
+                .chain(heroes.iter().flat_map(|h| h.keys()))
+                .collect();
+
+            for slot in slots {
+                spectated.push(Spectated {
+                    team,
+                    slot,
+                    player: players.and_then(|p| p.get(slot)),
+                    hero: heroes.and_then(|h| h.get(slot)),
+                });
+            }
+        }
+
+        if spectated.is_empty() {
+            for (slot, player) in self.allplayers.iter().flatten() {
+                let team = match player.team_name.as_deref() {
+                    Some("radiant") => TEAM_RADIANT,
+                    Some("dire") => TEAM_DIRE,
+                    _ => continue,
+                };
+                spectated.push(Spectated {
+                    team,
+                    slot,
+                    player: Some(player),
+                    hero: None,
+                });
+            }
+        }
+        spectated
+    }
+
+    /// Clock times of all events of given type
+    pub fn event_clock_times<'a>(&'a self, event_type: &'a str) -> impl Iterator<Item = i32> + 'a {
+        self.events
+            .iter()
+            .flatten()
+            .filter(move |e| e.event_type.as_deref() == Some(event_type))
+            .filter_map(move |e| self.map.as_ref()?.to_clock_time(e.game_time?))
+    }
+
+    /// Whether given key was added with this update (e.g. `/items/slot0`)
+    pub fn is_added(&self, pointer: &str) -> bool {
+        self.added.pointer(pointer) == Some(&Value::Bool(true))
+    }
+}
+
+/// Player & hero of one player slot when spectating
+pub struct Spectated<'a> {
+    /// `team2` (radiant) or `team3` (dire)
+    pub team: &'static str,
+    pub slot: &'a str,
+    pub player: Option<&'a Player>,
+    pub hero: Option<&'a Hero>,
+}
+
+impl<'a> Spectated<'a> {
+    pub fn has_buyback(&self) -> bool {
+        self.hero.map_or(false, |h| h.has_buyback(self.player))
+    }
+}
+
+pub const EVENT_ROSHAN_KILLED: &'static str = "roshan_killed";
+pub const EVENT_AEGIS_PICKED_UP: &'static str = "aegis_picked_up";
+pub const EVENT_AEGIS_DENIED: &'static str = "aegis_denied";
+
+#[derive(Deserialize, Debug)]
+pub struct Event {
+    pub game_time: Option<i32>,
+    pub event_type: Option<String>,
+    pub team: Option<String>,
+    pub player_id: Option<i32>,
+}
+
+/// Previous values of the keys changed by the update
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Previously {
+    #[serde(deserialize_with = "lenient")]
+    pub map: Option<Map>,
+    #[serde(deserialize_with = "lenient")]
+    pub player: Option<Player>,
+    #[serde(deserialize_with = "lenient")]
+    pub hero: Option<Hero>,
+    #[serde(deserialize_with = "lenient")]
+    pub abilities: Option<Abilities>,
+    #[serde(deserialize_with = "lenient")]
+    pub items: Option<Items>,
+}
+
+/// Whole section might be reported as `true` instead of its previous values
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    Ok(serde_json::from_value(Value::deserialize(deserializer)?).ok())
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Auth {
+    pub token: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -46,80 +230,258 @@ pub struct Provider {
 
 #[derive(Deserialize, Debug)]
 pub struct Map {
-    pub name: String,
-    pub matchid: String,
-    pub game_time: i32,
-    pub clock_time: i32,
-    pub daytime: bool,
-    pub nightstalker_night: bool,
-    pub game_state: String,
-    pub paused: bool,
-    pub win_team: String,
-    pub customgamename: String,
-    pub ward_purchase_cooldown: i32,
-}
-//
-// #[derive(Deserialize, Debug)]
-// pub struct Player {
-//     steamid: String,
-//     name: String,
-//     activity: String,
-//     kills: u32,
-//     deaths: u32,
-//     assists: u32,
-//     last_hits: u32,
-//     denies: u32,
-//     kill_streak: u32,
-//     commands_issued: u32,
-//     //kill_list
-//     team_name: String,
-//     gold: u32,
-//     gold_reliable: u32,
-//     gold_unreliable: u32,
-//     gold_from_hero_kills: u32,
-//     gold_from_creep_kills: u32,
-//     gold_from_income: u32,
-//     gold_from_shared: u32,
-//     gpm: u32,
-//     xpm: u32
-// }
-//
-// #[derive(Deserialize, Debug)]
-// pub struct Hero {
-//     xpos: i32,
-//     ypos: i32,
-//     id: u32,
-//     name: String,
-//     level: u8,
-//     alive: bool,
-//     respawn_seconds: u32,
-//     buyback_cost: u32,
-//     buyback_cooldown: u32,
-//     health: u32,
-//     max_health: u32,
-//     health_percent: u8,
-//     mana: u32,
-//     max_mana: u32,
-//     mana_percent: u8,
-//     silenced: bool,
-//     stunned: bool,
-//     disarmed: bool,
-//     magicimmune: bool,
-//     hexed: bool,
-//     muted: bool,
-//     #[serde(rename = "break")]
-//     is_break: bool,
-//     smoked: bool,
-//     has_debuff: bool,
-//     talent_1: bool,
-//     talent_2: bool,
-//     talent_3: bool,
-//     talent_4: bool,
-//     talent_5: bool,
-//     talent_6: bool,
-//     talent_7: bool,
-//     talent_8: bool
-// }
+    pub name: Option<String>,
+    pub matchid: Option<String>,
+    pub game_time: Option<i32>,
+    pub clock_time: Option<i32>,
+    pub daytime: Option<bool>,
+    pub nightstalker_night: Option<bool>,
+    pub game_state: Option<String>,
+    pub paused: Option<bool>,
+    pub win_team: Option<String>,
+    pub customgamename: Option<String>,
+    pub ward_purchase_cooldown: Option<i32>,
+    /// `alive`, `respawn_base` or `respawn_variable`
+    pub roshan_state: Option<String>,
+}
+
+impl Map {
+    /// Converts game time (e.g. of an event) to clock time
+    pub fn to_clock_time(&self, game_time: i32) -> Option<i32> {
+        let offset = self.game_time? - self.clock_time?;
+        Some(game_time - offset)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Player {
+    pub steamid: Option<String>,
+    pub name: Option<String>,
+    pub activity: Option<String>,
+    pub kills: Option<u32>,
+    pub deaths: Option<u32>,
+    pub assists: Option<u32>,
+    pub last_hits: Option<u32>,
+    pub denies: Option<u32>,
+    pub kill_streak: Option<u32>,
+    pub commands_issued: Option<u32>,
+    /// Kills per victim keyed as `victimid_<player slot>`
+    pub kill_list: Option<HashMap<String, u32>>,
+    pub team_name: Option<String>,
+    pub gold: Option<u32>,
+    pub gold_reliable: Option<u32>,
+    pub gold_unreliable: Option<u32>,
+    pub gold_from_hero_kills: Option<u32>,
+    pub gold_from_creep_kills: Option<u32>,
+    pub gold_from_income: Option<u32>,
+    pub gold_from_shared: Option<u32>,
+    pub gpm: Option<u32>,
+    pub xpm: Option<u32>,
+    pub net_worth: Option<u32>,
+    /// Radiant players keyed by their slot (`player0`, ...), present only when spectating
+    pub team2: Option<BTreeMap<String, Player>>,
+    /// Dire players keyed by their slot (`player5`, ...), present only when spectating
+    pub team3: Option<BTreeMap<String, Player>>,
+}
+
+impl Player {
+    pub fn is_team_view(&self) -> bool {
+        self.team2.is_some() || self.team3.is_some()
+    }
+
+    pub fn team(&self, team: &str) -> Option<&BTreeMap<String, Player>> {
+        match team {
+            TEAM_RADIANT => self.team2.as_ref(),
+            TEAM_DIRE => self.team3.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Levels unlocking the next talent tier
+pub const TALENT_LEVELS: [u8; 4] = [10, 15, 20, 25];
+pub const MAX_LEVEL: u8 = 30;
+
+#[derive(Deserialize, Debug)]
+pub struct Hero {
+    pub xpos: Option<i32>,
+    pub ypos: Option<i32>,
+    pub id: Option<i32>,
+    pub name: Option<String>,
+    pub level: Option<u8>,
+    pub alive: Option<bool>,
+    pub respawn_seconds: Option<u32>,
+    pub buyback_cost: Option<u32>,
+    pub buyback_cooldown: Option<u32>,
+    pub health: Option<u32>,
+    pub max_health: Option<u32>,
+    pub health_percent: Option<u8>,
+    pub mana: Option<u32>,
+    pub max_mana: Option<u32>,
+    pub mana_percent: Option<u8>,
+    pub silenced: Option<bool>,
+    pub stunned: Option<bool>,
+    pub disarmed: Option<bool>,
+    pub magicimmune: Option<bool>,
+    pub hexed: Option<bool>,
+    pub muted: Option<bool>,
+    #[serde(rename = "break")]
+    pub is_break: Option<bool>,
+    pub smoked: Option<bool>,
+    pub has_debuff: Option<bool>,
+    pub aghanims_scepter: Option<bool>,
+    pub aghanims_shard: Option<bool>,
+    pub talent_1: Option<bool>,
+    pub talent_2: Option<bool>,
+    pub talent_3: Option<bool>,
+    pub talent_4: Option<bool>,
+    pub talent_5: Option<bool>,
+    pub talent_6: Option<bool>,
+    pub talent_7: Option<bool>,
+    pub talent_8: Option<bool>,
+    /// Radiant heroes keyed by player slot, present only when spectating
+    pub team2: Option<BTreeMap<String, Hero>>,
+    /// Dire heroes keyed by player slot, present only when spectating
+    pub team3: Option<BTreeMap<String, Hero>>,
+}
+
+impl Hero {
+    /// Player of the hero has enough reliable gold for the buyback and it's not on cooldown
+    pub fn has_buyback(&self, player: Option<&Player>) -> bool {
+        let has_enough_gold = match (player.and_then(|p| p.gold_reliable), self.buyback_cost) {
+            (Some(gold), Some(cost)) => gold > cost,
+            _ => false,
+        };
+
+        has_enough_gold && self.buyback_cooldown.unwrap_or_default() == 0
+    }
+
+    pub fn team(&self, team: &str) -> Option<&BTreeMap<String, Hero>> {
+        match team {
+            TEAM_RADIANT => self.team2.as_ref(),
+            TEAM_DIRE => self.team3.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Talents the hero could have learned at its level but did not
+    pub fn unspent_talents(&self) -> usize {
+        let level = self.level.unwrap_or_default();
+        let available = TALENT_LEVELS.iter().filter(|l| **l <= level).count();
+        let learned = self.talents().iter().filter(|t| **t == Some(true)).count();
+        available.saturating_sub(learned)
+    }
+
+    /// Talent flags ordered from `talent_1` to `talent_8`
+    pub fn talents(&self) -> [Option<bool>; 8] {
+        [
+            self.talent_1,
+            self.talent_2,
+            self.talent_3,
+            self.talent_4,
+            self.talent_5,
+            self.talent_6,
+            self.talent_7,
+            self.talent_8,
+        ]
+    }
+}
+
+/// Hero abilities keyed by their slot (`ability0`, `ability1`, ...)
+pub type Abilities = BTreeMap<String, Ability>;
+
+#[derive(Deserialize, Debug)]
+pub struct Ability {
+    pub name: Option<String>,
+    pub level: Option<u8>,
+    pub can_cast: Option<bool>,
+    pub passive: Option<bool>,
+    pub ability_active: Option<bool>,
+    pub cooldown: Option<u32>,
+    pub ultimate: Option<bool>,
+}
+
+/// Item name reported by the game for an empty slot
+pub const EMPTY_ITEM: &'static str = "empty";
+pub const ITEM_AEGIS: &'static str = "item_aegis";
+pub const ITEM_HAND_OF_MIDAS: &'static str = "item_hand_of_midas";
+
+#[derive(Deserialize, Debug)]
+pub struct Items {
+    pub slot0: Option<Item>,
+    pub slot1: Option<Item>,
+    pub slot2: Option<Item>,
+    pub slot3: Option<Item>,
+    pub slot4: Option<Item>,
+    pub slot5: Option<Item>,
+    pub slot6: Option<Item>,
+    pub slot7: Option<Item>,
+    pub slot8: Option<Item>,
+    pub stash0: Option<Item>,
+    pub stash1: Option<Item>,
+    pub stash2: Option<Item>,
+    pub stash3: Option<Item>,
+    pub stash4: Option<Item>,
+    pub stash5: Option<Item>,
+    pub teleport0: Option<Item>,
+    pub neutral0: Option<Item>,
+}
+
+impl Items {
+    /// All slots paired with the slot name used by the game
+    pub fn slots(&self) -> Vec<(&'static str, Option<&Item>)> {
+        vec![
+            ("slot0", self.slot0.as_ref()),
+            ("slot1", self.slot1.as_ref()),
+            ("slot2", self.slot2.as_ref()),
+            ("slot3", self.slot3.as_ref()),
+            ("slot4", self.slot4.as_ref()),
+            ("slot5", self.slot5.as_ref()),
+            ("slot6", self.slot6.as_ref()),
+            ("slot7", self.slot7.as_ref()),
+            ("slot8", self.slot8.as_ref()),
+            ("stash0", self.stash0.as_ref()),
+            ("stash1", self.stash1.as_ref()),
+            ("stash2", self.stash2.as_ref()),
+            ("stash3", self.stash3.as_ref()),
+            ("stash4", self.stash4.as_ref()),
+            ("stash5", self.stash5.as_ref()),
+            ("teleport0", self.teleport0.as_ref()),
+            ("neutral0", self.neutral0.as_ref()),
+        ]
+    }
+
+    pub fn slot(&self, slot: &str) -> Option<&Item> {
+        self.slots()
+            .into_iter()
+            .find(|(name, _)| *name == slot)
+            .and_then(|(_, item)| item)
+    }
+
+    /// First slot holding item with given name
+    pub fn find(&self, name: &str) -> Option<(&'static str, &Item)> {
+        self.slots()
+            .into_iter()
+            .filter_map(|(slot, item)| item.map(|item| (slot, item)))
+            .find(|(_, item)| item.name.as_deref() == Some(name))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Item {
+    pub name: Option<String>,
+    pub purchaser: Option<i32>,
+    pub can_cast: Option<bool>,
+    pub cooldown: Option<u32>,
+    pub passive: Option<bool>,
+    pub charges: Option<u32>,
+}
+
+impl Item {
+    pub fn is_empty(&self) -> bool {
+        self.name.as_deref().map_or(true, |name| name == EMPTY_ITEM)
+    }
+}
 
 #[derive(Serialize)]
 pub struct DotaDir(PathBuf);
@@ -210,3 +572,74 @@ fn read_lines(path: &Path) -> Box<dyn Iterator<Item = io::Result<String>>> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Game, TEAM_DIRE, TEAM_RADIANT};
+
+    #[test]
+    fn spectated_teams() {
+        let game: Game = serde_json::from_str(
+            r#"{
+                "player": {
+                    "team2": {"player0": {"gold_reliable": 1000, "net_worth": 5000}},
+                    "team3": {"player5": {"gold_reliable": 100}}
+                },
+                "hero": {
+                    "team2": {"player0": {"name": "npc_dota_hero_axe", "buyback_cost": 500, "buyback_cooldown": 0}},
+                    "team3": {
+                        "player5": {"buyback_cost": 500, "buyback_cooldown": 0},
+                        "player6": {"buyback_cost": 500, "buyback_cooldown": 0}
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(game.is_spectating(), true);
+
+        let spectated = game.spectated();
+        let slots: Vec<_> = spectated.iter().map(|s| (s.team, s.slot)).collect();
+        assert_eq!(
+            slots,
+            vec![
+                (TEAM_RADIANT, "player0"),
+                (TEAM_DIRE, "player5"),
+                (TEAM_DIRE, "player6")
+            ]
+        );
+        assert_eq!(spectated[0].player.and_then(|p| p.net_worth), Some(5000));
+        assert_eq!(spectated[0].has_buyback(), true);
+        assert_eq!(spectated[1].has_buyback(), false);
+        assert_eq!(spectated[2].player.is_none(), true);
+        assert_eq!(spectated[2].has_buyback(), false);
+    }
+
+    #[test]
+    fn spectated_allplayers() {
+        let game: Game = serde_json::from_str(
+            r#"{
+                "allplayers": {
+                    "p0": {"team_name": "radiant", "net_worth": 100},
+                    "p1": {"team_name": "dire", "net_worth": 200},
+                    "p2": {"team_name": "spectator"}
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(game.is_spectating(), true);
+
+        let spectated = game.spectated();
+        let slots: Vec<_> = spectated.iter().map(|s| (s.team, s.slot)).collect();
+        assert_eq!(slots, vec![(TEAM_RADIANT, "p0"), (TEAM_DIRE, "p1")]);
+        assert_eq!(spectated[1].hero.is_none(), true);
+    }
+
+    #[test]
+    fn playing_is_not_spectating() {
+        let game: Game =
+            serde_json::from_str(r#"{"player": {"gold_reliable": 100}, "hero": {"level": 1}}"#)
+                .unwrap();
+        assert_eq!(game.is_spectating(), false);
+        assert_eq!(game.spectated().len(), 0);
+    }
+}
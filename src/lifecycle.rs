@@ -0,0 +1,237 @@
+use crate::delta::GameEvent;
+use crate::game::{Game, GAME_STATE_IN_PROGRESS, GAME_STATE_PRE_GAME};
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchState {
+    Idle,
+    HeroSelection,
+    StrategyTime,
+    Showcase,
+    PreGame,
+    InProgress,
+    PostGame,
+    Disconnected,
+}
+
+impl MatchState {
+    pub fn of(game: &Game) -> Self {
+        match game.game_state() {
+            Some("DOTA_GAMERULES_STATE_HERO_SELECTION") => MatchState::HeroSelection,
+            Some("DOTA_GAMERULES_STATE_STRATEGY_TIME") => MatchState::StrategyTime,
+            Some("DOTA_GAMERULES_STATE_TEAM_SHOWCASE")
+            | Some("DOTA_GAMERULES_STATE_WAIT_FOR_MAP_TO_LOAD") => MatchState::Showcase,
+            Some(GAME_STATE_PRE_GAME) => MatchState::PreGame,
+            Some(GAME_STATE_IN_PROGRESS) => MatchState::InProgress,
+            Some("DOTA_GAMERULES_STATE_POST_GAME") => MatchState::PostGame,
+            Some("DOTA_GAMERULES_STATE_DISCONNECT") => MatchState::Disconnected,
+            _ => MatchState::Idle,
+        }
+    }
+}
+
+impl MatchState {
+    fn is_before_start(self) -> bool {
+        match self {
+            MatchState::HeroSelection
+            | MatchState::StrategyTime
+            | MatchState::Showcase
+            | MatchState::PreGame => true,
+            _ => false,
+        }
+    }
+}
+
+/// Result of single game state update applied to the lifecycle
+pub struct LifecycleUpdate {
+    /// Update belongs to different match than the previous ones
+    pub new_match: bool,
+    pub events: Vec<GameEvent>,
+}
+
+/// Tracks the match (keyed by its id) and its state
+pub struct MatchLifecycle {
+    match_id: Option<String>,
+    state: MatchState,
+    /// Match was seen in progress, start is announced only when seen before it
+    started: bool,
+    ended: bool,
+}
+
+impl MatchLifecycle {
+    pub fn new() -> Self {
+        MatchLifecycle {
+            match_id: None,
+            state: MatchState::Idle,
+            started: false,
+            ended: false,
+        }
+    }
+
+    pub fn state(&self) -> MatchState {
+        self.state
+    }
+
+    /// Game state update belongs to different match than the previous ones
+    pub fn is_new_match(&self, update: &Game) -> bool {
+        let match_id = update.map.as_ref().and_then(|m| m.matchid.as_ref());
+        match (&self.match_id, match_id) {
+            (Some(old), Some(new)) => old != new,
+            _ => false,
+        }
+    }
+
+    /// Applies merged game snapshot, match start & end are reported once per match
+    pub fn update(&mut self, game: &Game) -> LifecycleUpdate {
+        let mut events = Vec::new();
+
+        let new_match = self.is_new_match(game);
+        if new_match {
+            self.started = false;
+            self.ended = false;
+        }
+        if let Some(match_id) = game.map.as_ref().and_then(|m| m.matchid.clone()) {
+            self.match_id = Some(match_id);
+        }
+
+        let state = MatchState::of(game);
+        if new_match || state != self.state {
+            events.push(GameEvent::MatchStateChanged {
+                old: self.state,
+                new: state,
+            });
+
+            let match_id = self.match_id.clone().unwrap_or_default();
+            match state {
+                MatchState::InProgress if !self.started => {
+                    self.started = true;
+                    // not announced when joined (or restarted) in the middle of the match
+                    if self.state.is_before_start() {
+                        events.push(GameEvent::MatchStarted { match_id });
+                    }
+                }
+                MatchState::PostGame if self.started && !self.ended => {
+                    self.ended = true;
+                    events.push(GameEvent::MatchEnded { match_id });
+                }
+                _ => {}
+            }
+            self.state = state;
+        }
+
+        LifecycleUpdate { new_match, events }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::delta::GameEvent;
+    use crate::game::Game;
+    use crate::lifecycle::{MatchLifecycle, MatchState};
+
+    fn game(match_id: &str, game_state: &str) -> Game {
+        serde_json::from_str(&format!(
+            r#"{{"map": {{"matchid": "{}", "game_state": "{}"}}}}"#,
+            match_id, game_state
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn match_started_and_ended() {
+        let mut lifecycle = MatchLifecycle::new();
+
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_PRE_GAME"));
+        assert_eq!(update.new_match, false);
+        assert_eq!(lifecycle.state(), MatchState::PreGame);
+
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_PRE_GAME"));
+        assert_eq!(update.events, vec![]);
+
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS"));
+        assert_eq!(
+            update.events,
+            vec![
+                GameEvent::MatchStateChanged {
+                    old: MatchState::PreGame,
+                    new: MatchState::InProgress,
+                },
+                GameEvent::MatchStarted {
+                    match_id: "1".to_string()
+                },
+            ]
+        );
+
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_POST_GAME"));
+        assert_eq!(
+            update.events[1],
+            GameEvent::MatchEnded {
+                match_id: "1".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn reconnect_to_same_match() {
+        let mut lifecycle = MatchLifecycle::new();
+        lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_PRE_GAME"));
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS"));
+        assert_eq!(update.events.len(), 2);
+
+        lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_DISCONNECT"));
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS"));
+        assert_eq!(
+            update.events,
+            vec![GameEvent::MatchStateChanged {
+                old: MatchState::Disconnected,
+                new: MatchState::InProgress,
+            }]
+        );
+
+        // game closed and started again
+        lifecycle.update(&Game::default());
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS"));
+        assert_eq!(update.events.len(), 1);
+
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_POST_GAME"));
+        assert_eq!(update.events.len(), 2);
+        lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_DISCONNECT"));
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_POST_GAME"));
+        assert_eq!(update.events.len(), 1);
+    }
+
+    #[test]
+    fn joined_match_in_progress() {
+        let mut lifecycle = MatchLifecycle::new();
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS"));
+        assert_eq!(update.events.len(), 1);
+
+        let update = lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_POST_GAME"));
+        assert_eq!(
+            update.events[1],
+            GameEvent::MatchEnded {
+                match_id: "1".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn new_match_id() {
+        let mut lifecycle = MatchLifecycle::new();
+        lifecycle.update(&game("1", "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS"));
+
+        // main menu without map keeps the last match
+        assert_eq!(lifecycle.is_new_match(&Game::default()), false);
+        let update = lifecycle.update(&Game::default());
+        assert_eq!(update.new_match, false);
+        assert_eq!(lifecycle.state(), MatchState::Idle);
+
+        assert_eq!(
+            lifecycle.is_new_match(&game("2", "DOTA_GAMERULES_STATE_HERO_SELECTION")),
+            true
+        );
+        let update = lifecycle.update(&game("2", "DOTA_GAMERULES_STATE_HERO_SELECTION"));
+        assert_eq!(update.new_match, true);
+        assert_eq!(lifecycle.state(), MatchState::HeroSelection);
+    }
+}
@@ -2,9 +2,16 @@
 #![windows_subsystem = "windows"]
 
 mod audio;
+mod client;
+mod delta;
+mod detector;
 mod embed;
 mod game;
+mod lifecycle;
+mod preset;
+mod session;
 mod settings;
+mod snapshot;
 
 #[macro_use]
 extern crate rocket;
@@ -15,23 +22,30 @@ use rocket_contrib::json::{Json, JsonValue};
 use rocket_contrib::serve::Options;
 
 use crate::audio::AudioPlayer;
-use crate::embed::{EmbedFile, EmbedFiles};
-use crate::game::{get_dota2_dir, DotaDir, Game, GAME_STATE_INTEGRATION_FILE_NAME};
-use crate::settings::{NotifyAction, OnClock, Settings};
+use crate::client::{ClientInfo, Clients};
+use crate::embed::{Disposition, EmbedFiles};
+use crate::game::{
+    get_dota2_dir, DotaDir, Game, AUTH_TOKEN_PLACEHOLDER, GAME_STATE_INTEGRATION_FILE_NAME,
+};
+use crate::preset::{Preset, PresetApply};
+use crate::settings::{NotifyAction, Settings};
 use rocket::fairing::AdHoc;
 use rocket::figment::Figment;
 use rocket::http::{Header, Status};
 use rocket::logger::LogLevel;
 use rocket::response::status::Custom;
-use rocket::response::{Debug, Redirect};
-use rocket::{Config, State};
+use rocket::response::{Debug, Redirect, Responder};
+use rocket::{response, Config, Request, State};
 use rust_embed::RustEmbed;
+use serde::Deserialize;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::io::Write;
-use std::ops::{Deref, DerefMut};
-use std::sync::Mutex;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::thread::spawn;
 use systray::Application;
 
@@ -42,100 +56,56 @@ const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 struct Asset;
 
 struct Runtime {
-    player: AudioPlayer,
+    player: Arc<AudioPlayer>,
+    /// Players of the non-default output devices keyed by device name
+    outputs: Mutex<HashMap<String, Arc<AudioPlayer>>>,
     settings: Mutex<Settings>,
-    last_buyback: Mutex<bool>,
+    clients: Mutex<Clients>,
+}
+
+impl Runtime {
+    /// Player of the output device with given name, default output is used when not set
+    fn output_player(&self, output: Option<&str>, volume: f32) -> Arc<AudioPlayer> {
+        match (output.filter(|o| !o.is_empty()), self.outputs.lock()) {
+            (Some(output), Ok(mut outputs)) => outputs
+                .entry(output.to_string())
+                .or_insert_with(|| {
+                    let player = AudioPlayer::with_output(Some(output.to_string()));
+                    player.set_volume(volume);
+                    Arc::new(player)
+                })
+                .clone(),
+            _ => self.player.clone(),
+        }
+    }
 }
 
 #[post("/", format = "json", data = "<state>")]
-fn game_state_update(r: State<Runtime>, state: Json<Game>) {
-    if let Ok(s) = r.settings.lock() {
-        if s.global.suspend_all {
-            return;
+fn game_state_update(
+    r: State<Runtime>,
+    remote: SocketAddr,
+    state: Json<Value>,
+) -> Result<(), Status> {
+    let update = Game::deserialize(&*state).map_err(|_| Status::UnprocessableEntity)?;
+
+    if let (Ok(s), Ok(mut clients)) = (r.settings.lock(), r.clients.lock()) {
+        let token = update.auth.as_ref().and_then(|a| a.token.as_deref());
+        if !s.global.is_authorized(token) {
+            return Err(Status::Unauthorized);
         }
 
-        if let Some(map) = &state.map {
-            if let Some(Value::String(prev_state)) = state.previously.pointer("/map/game_state") {
-                println!("{} -> {}", prev_state, map.game_state);
-            }
+        let id = clients.id(&update, remote.ip());
+        let config = s.client(&id);
+        let profile = s.profile(config.and_then(|c| c.profile.as_deref()));
+        let player = r.output_player(config.and_then(|c| c.output.as_deref()), s.global.volume);
 
-            if map.game_state == "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS"
-                || map.game_state == "DOTA_GAMERULES_STATE_PRE_GAME"
-            {
-                if s.buyback_ready.notify.enabled
-                    && (state.previously.pointer("/player/gold_reliable").is_some()
-                        || state.previously.pointer("/hero/buyback_cost").is_some()
-                        || state.previously.pointer("/hero/buyback_cooldown").is_some())
-                {
-                    let gold_reliable = state.player["gold_reliable"].as_i64();
-                    let buyback_cost = state.hero["buyback_cost"].as_i64();
-                    let buyback_cooldown = state.hero["buyback_cooldown"].as_i64();
-
-                    let has_enough_gold = match (gold_reliable, buyback_cost) {
-                        (Some(gold), Some(cost)) => gold - cost > 0,
-                        _ => false,
-                    };
-
-                    let has_buyback = has_enough_gold && buyback_cooldown.unwrap_or_default() == 0;
-                    if let Ok(mut last_buyback) = r.last_buyback.lock() {
-                        if has_buyback && !*last_buyback.deref() {
-                            *last_buyback.deref_mut() = true;
-                            s.buyback_ready.notify.action.trigger(&r.player);
-                        } else if !has_buyback && *last_buyback.deref() {
-                            *last_buyback.deref_mut() = false;
-                        }
-                    }
-                }
-
-                if s.observer_wards.notify.enabled
-                    && map.ward_purchase_cooldown == s.observer_wards.notify.before_sec as i32
-                    && state
-                        .previously
-                        .pointer("/map/ward_purchase_cooldown")
-                        .is_some()
-                {
-                    println!(
-                        "{} there are observer wards about to spawn in {} sec",
-                        map.clock_time, s.observer_wards.notify.before_sec
-                    );
-                    s.observer_wards.notify.action.trigger(&r.player);
-                }
-
-                // handle OnClock actions
-                if !state.previously.pointer("/map/clock_time").is_some() {
-                    return;
-                }
-
-                if s.bounty_rune.on_clock(map.clock_time, &r.player) {
-                    println!(
-                        "{} there are bounty runes about to spawn in {} sec",
-                        map.clock_time, s.bounty_rune.notify.before_sec
-                    );
-                }
-
-                if s.power_rune.on_clock(map.clock_time, &r.player) {
-                    println!(
-                        "{} there are power runes about to spawn in {} sec",
-                        map.clock_time, s.power_rune.notify.before_sec
-                    );
-                }
-
-                if s.tomb_of_knowledge.on_clock(map.clock_time, &r.player) {
-                    println!(
-                        "{} there is tomb of knowledge about to spawn in {} sec",
-                        map.clock_time, s.tomb_of_knowledge.notify.before_sec
-                    );
-                }
-
-                if s.neutral_items.on_clock(map.clock_time, &r.player) {
-                    println!(
-                        "{} there are neutral items that can be dropped in about {} sec",
-                        map.clock_time, s.neutral_items.notify.before_sec
-                    );
-                }
-            }
-        }
+        clients
+            .get(&id, remote.ip())
+            .session
+            .update(&state, &update, profile, s.global.suspend_all, &player)
+            .map_err(|_| Status::UnprocessableEntity)?;
     }
+    Ok(())
 }
 
 #[get("/")]
@@ -146,7 +116,7 @@ async fn index() -> Redirect {
 #[get("/settings")]
 fn settings_load(r: State<Runtime>) -> Result<Json<Settings>, ()> {
     match r.settings.lock() {
-        Ok(settings) => Ok(Json(settings.clone())),
+        Ok(settings) => Ok(Json(settings.redacted())),
         Err(_) => Err(()),
     }
 }
@@ -157,21 +127,127 @@ fn settings_save(r: State<Runtime>, settings: Json<Settings>) -> Result<(), Debu
         .lock()
         .map_err(|_| io::Error::new(io::ErrorKind::Other, "Lock failed"))
         .and_then(|mut s| {
+            // auth token is managed only by install & rotate
+            let auth_token = s.global.auth_token.take();
             *s = settings.into_inner();
+            s.global.auth_token = auth_token;
 
             // apply volume change if any
             r.player.set_volume(s.global.volume);
+            if let Ok(outputs) = r.outputs.lock() {
+                outputs
+                    .values()
+                    .for_each(|player| player.set_volume(s.global.volume));
+            }
 
             s.save()
         })
         .map_err(Debug)
 }
 
+#[get("/clients")]
+fn clients_load(r: State<Runtime>) -> Result<Json<Vec<ClientInfo>>, ()> {
+    match r.clients.lock() {
+        Ok(clients) => Ok(Json(clients.info())),
+        Err(_) => Err(()),
+    }
+}
+
+#[get("/outputs")]
+fn outputs_load() -> Json<Vec<String>> {
+    Json(AudioPlayer::outputs())
+}
+
+#[get("/presets")]
+fn presets_load() -> Json<Vec<Preset>> {
+    Json(Preset::all())
+}
+
+#[post("/presets/apply", format = "json", data = "<apply>")]
+fn preset_apply(
+    r: State<Runtime>,
+    apply: Json<PresetApply>,
+) -> Result<Json<Settings>, Custom<String>> {
+    let preset = Preset::find(&apply.patch)
+        .ok_or_else(|| Custom(Status::NotFound, format!("Unknown preset {}", apply.patch)))?;
+
+    let mut s = r
+        .settings
+        .lock()
+        .map_err(|_| Custom(Status::InternalServerError, "Lock failed".to_string()))?;
+    let profile = s
+        .profile_mut(apply.profile.as_deref())
+        .ok_or_else(|| Custom(Status::NotFound, "Unknown profile".to_string()))?;
+    preset.apply(profile);
+
+    s.save()
+        .map_err(|e| Custom(Status::InternalServerError, e.to_string()))?;
+    Ok(Json(s.redacted()))
+}
+
 #[post("/trigger", format = "json", data = "<action>")]
 fn trigger(r: State<Runtime>, action: Json<NotifyAction>) {
     action.trigger(&r.player);
 }
 
+/// Game state integration file with the auth token filled in
+struct IntegrationFile(String);
+
+impl<'r> Responder<'r, 'static> for IntegrationFile {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = self.0.respond_to(req)?;
+        response.set_header(Header::new(
+            "Content-Disposition",
+            format!(
+                "{}; filename=\"{}\"",
+                Disposition::Attachment.to_str(),
+                GAME_STATE_INTEGRATION_FILE_NAME
+            ),
+        ));
+        Ok(response)
+    }
+}
+
+fn installed_integration_file() -> Option<PathBuf> {
+    get_dota2_dir().map(|dir| dir.integration_dir().join(GAME_STATE_INTEGRATION_FILE_NAME))
+}
+
+fn integration_file_content(r: &Runtime) -> Result<String, Custom<String>> {
+    let token = r
+        .settings
+        .lock()
+        .map_err(|_| "Lock failed".to_string())
+        .and_then(|s| {
+            s.auth_token()
+                .map(str::to_string)
+                .ok_or_else(|| "Missing auth token".to_string())
+        })
+        .map_err(|e| Custom(Status::InternalServerError, e))?;
+
+    Asset::get(GAME_STATE_INTEGRATION_FILE_NAME)
+        .map(|data| String::from_utf8_lossy(&data).replace(AUTH_TOKEN_PLACEHOLDER, &token))
+        .ok_or_else(|| {
+            Custom(
+                Status::InternalServerError,
+                "Missing game state integration file".to_string(),
+            )
+        })
+}
+
+fn write_integration_file(r: &Runtime) -> Result<(), Custom<String>> {
+    let content = integration_file_content(r)?;
+
+    match installed_integration_file() {
+        Some(file) => File::create(file)
+            .and_then(|mut f| f.write_all(content.as_bytes()))
+            .map_err(|e| Custom(Status::InternalServerError, e.to_string())),
+        None => Err(Custom(
+            Status::InternalServerError,
+            "Could not detect Dota2 installation directory".to_string(),
+        )),
+    }
+}
+
 #[get("/install")]
 fn install() -> JsonValue {
     let dota_dir = get_dota2_dir();
@@ -195,26 +271,28 @@ fn install() -> JsonValue {
 }
 
 #[post("/install")]
-fn install_post() -> Result<(), Custom<String>> {
-    let integration_file = Asset::get("gamestate_integration_announcer.cfg");
-
-    if let Some(data) = integration_file {
-        if let Some(dir) = get_dota2_dir() {
-            return File::create(dir.integration_dir().join(GAME_STATE_INTEGRATION_FILE_NAME))
-                .and_then(|mut f| f.write_all(&data))
-                .map_err(|e| Custom(Status::InternalServerError, e.to_string()));
-        }
-    }
+fn install_post(r: State<Runtime>) -> Result<(), Custom<String>> {
+    write_integration_file(&r)
+}
+
+#[post("/auth/rotate")]
+fn auth_rotate(r: State<Runtime>) -> Result<(), Custom<String>> {
+    r.settings
+        .lock()
+        .map_err(|_| io::Error::new(io::ErrorKind::Other, "Lock failed"))
+        .and_then(|mut s| s.rotate_auth_token())
+        .map_err(|e| Custom(Status::InternalServerError, e.to_string()))?;
 
-    Err(Custom(
-        Status::InternalServerError,
-        "Could not detect Dota2 installation directory".to_string(),
-    ))
+    // refresh already installed file, game will pick the new token after restart
+    if installed_integration_file().map_or(false, |f| f.exists()) {
+        return write_integration_file(&r);
+    }
+    Ok(())
 }
 
 #[get("/gamestate_integration_announcer.cfg")]
-async fn integration_file() -> Option<EmbedFile<Asset>> {
-    <EmbedFile<Asset>>::of_attachment("gamestate_integration_announcer.cfg").ok()
+fn integration_file(r: State<Runtime>) -> Result<IntegrationFile, Custom<String>> {
+    integration_file_content(&r).map(IntegrationFile)
 }
 
 fn spawn_tray_icon(config: Config) {
@@ -232,8 +310,13 @@ fn build_tray_menu(app: &mut Application, config: Config) -> Result<(), systray:
     app.set_icon_from_resource("icon")?; // see build.rs for resource icon_id
     app.set_tooltip("Dota2 Announcer (by Smreki)")?;
 
+    // listening on all interfaces, the page is still available locally
+    let address = match config.address {
+        address if address.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        address => address,
+    };
     app.add_menu_item("Config Webpage", move |_| {
-        opener::open(format!("http://{}:{}", config.address, config.port))
+        opener::open(format!("http://{}:{}", address, config.port))
     })?;
 
     app.add_menu_separator()?;
@@ -248,19 +331,22 @@ fn build_tray_menu(app: &mut Application, config: Config) -> Result<(), systray:
 
 #[launch]
 fn rocket() -> rocket::Rocket {
-    let figment = Figment::from(Config::release_default()).merge(("log_level", LogLevel::Off));
-
     // load setting
     let settings = Settings::load();
 
+    let figment = Figment::from(Config::release_default())
+        .merge(("log_level", LogLevel::Off))
+        .merge(("address", settings.global.address));
+
     // create audio player and set volume form saved settings
     let player = AudioPlayer::new();
     player.set_volume(settings.global.volume);
 
     let runtime = Runtime {
-        player,
+        player: Arc::new(player),
+        outputs: Mutex::new(HashMap::new()),
         settings: Mutex::new(settings),
-        last_buyback: Mutex::new(false),
+        clients: Mutex::new(Clients::new()),
     };
 
     rocket::custom(figment)
@@ -268,7 +354,18 @@ fn rocket() -> rocket::Rocket {
         .mount("/", routes![index, game_state_update, integration_file])
         .mount(
             "/api",
-            routes![settings_load, settings_save, trigger, install, install_post],
+            routes![
+                settings_load,
+                settings_save,
+                clients_load,
+                outputs_load,
+                presets_load,
+                preset_apply,
+                trigger,
+                install,
+                install_post,
+                auth_rotate
+            ],
         )
         .manage(runtime)
         .attach(AdHoc::on_response("Version header", |_, res| {
@@ -0,0 +1,107 @@
+use crate::settings::{NeutralTier, Profile, Schedule, SpawnInfo};
+use serde::{Deserialize, Serialize};
+
+/// Spawn schedules of one game patch
+#[derive(Serialize, Debug, Clone)]
+pub struct Preset {
+    pub patch: &'static str,
+    pub bounty_rune: Schedule,
+    pub power_rune: Schedule,
+    pub tomb_of_knowledge: Schedule,
+    /// Clock time sec when each neutral item tier is unlocked
+    pub neutral_items: &'static [u16],
+}
+
+/// Request to apply a preset to the profile (default profile when not set)
+#[derive(Deserialize, Debug)]
+pub struct PresetApply {
+    pub patch: String,
+    pub profile: Option<String>,
+}
+
+impl Preset {
+    /// Bundled presets, the latest patch comes first
+    pub fn all() -> Vec<Preset> {
+        vec![
+            Preset {
+                patch: "7.33",
+                bounty_rune: Schedule::Every(SpawnInfo {
+                    first_sec: 0,
+                    interval_sec: 180,
+                    until_sec: None,
+                }),
+                power_rune: Schedule::Every(SpawnInfo {
+                    first_sec: 360,
+                    interval_sec: 120,
+                    until_sec: None,
+                }),
+                tomb_of_knowledge: Schedule::Every(SpawnInfo {
+                    first_sec: 600,
+                    interval_sec: 600,
+                    until_sec: None,
+                }),
+                neutral_items: &[420, 1020, 1620, 2220, 3600],
+            },
+            Preset {
+                patch: "7.28",
+                bounty_rune: Schedule::Every(SpawnInfo {
+                    first_sec: 0,
+                    interval_sec: 300,
+                    until_sec: None,
+                }),
+                power_rune: Schedule::Every(SpawnInfo {
+                    first_sec: 240,
+                    interval_sec: 120,
+                    until_sec: None,
+                }),
+                tomb_of_knowledge: Schedule::Every(SpawnInfo {
+                    first_sec: 600,
+                    interval_sec: 600,
+                    until_sec: None,
+                }),
+                neutral_items: &[420, 1020, 1620, 2220, 3600],
+            },
+        ]
+    }
+
+    pub fn find(patch: &str) -> Option<Preset> {
+        Self::all().into_iter().find(|p| p.patch == patch)
+    }
+
+    /// Replaces the spawn schedules of the profile, notifications are kept as they are
+    pub fn apply(&self, profile: &mut Profile) {
+        profile.bounty_rune.spawn = self.bounty_rune.clone();
+        profile.power_rune.spawn = self.power_rune.clone();
+        profile.tomb_of_knowledge.spawn = self.tomb_of_knowledge.clone();
+
+        let mut tiers = NeutralTier::schedule(self.neutral_items);
+        for tier in tiers.iter_mut() {
+            tier.action = profile
+                .neutral_items
+                .tiers
+                .iter()
+                .find(|t| t.tier == tier.tier)
+                .and_then(|t| t.action.clone());
+        }
+        profile.neutral_items.tiers = tiers;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::preset::Preset;
+    use crate::settings::{NotifyAction, Profile};
+
+    #[test]
+    fn apply_keeps_notifications() {
+        let mut profile = Profile::default();
+        profile.power_rune.notify.before_sec = 3;
+        profile.neutral_items.tiers[1].action = Some(NotifyAction::default());
+
+        Preset::find("7.33").unwrap().apply(&mut profile);
+        assert_eq!(profile.power_rune.spawn.next_spawn(0), Some(360));
+        assert_eq!(profile.power_rune.notify.before_sec, 3);
+        assert_eq!(profile.neutral_items.tiers[1].action.is_some(), true);
+        assert_eq!(profile.neutral_items.tiers[2].action.is_none(), true);
+    }
+}
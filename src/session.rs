@@ -0,0 +1,67 @@
+use crate::audio::AudioPlayer;
+use crate::delta::GameDelta;
+use crate::detector::{Context, Detectors};
+use crate::game::Game;
+use crate::lifecycle::{MatchLifecycle, MatchState};
+use crate::settings::Profile;
+use crate::snapshot::GameSnapshot;
+use serde_json::Value;
+
+/// Game tracking state, everything except the lifecycle is reset when new match starts
+pub struct Session {
+    lifecycle: MatchLifecycle,
+    snapshot: GameSnapshot,
+    detectors: Detectors,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Session {
+            lifecycle: MatchLifecycle::new(),
+            snapshot: GameSnapshot::new(),
+            detectors: Detectors::default(),
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        self.snapshot.game()
+    }
+
+    pub fn state(&self) -> MatchState {
+        self.lifecycle.state()
+    }
+
+    /// Applies raw game state update (`update` is its typed form),
+    /// detectors are not invoked when `suspended`
+    pub fn update(
+        &mut self,
+        state: &Value,
+        update: &Game,
+        profile: &Profile,
+        suspended: bool,
+        player: &AudioPlayer,
+    ) -> serde_json::Result<()> {
+        if self.lifecycle.is_new_match(update) {
+            self.snapshot = GameSnapshot::new();
+            self.detectors.reset();
+        }
+
+        self.snapshot.merge(state)?;
+        let lifecycle = self.lifecycle.update(self.snapshot.game());
+
+        if suspended {
+            return Ok(());
+        }
+
+        let mut delta = GameDelta::new(update);
+        delta.events.splice(0..0, lifecycle.events);
+
+        let ctx = Context {
+            game: self.snapshot.game(),
+            profile,
+            player,
+        };
+        self.detectors.handle(&delta, &ctx);
+        Ok(())
+    }
+}
@@ -1,44 +1,152 @@
 use crate::audio::AudioPlayer;
+use crate::game::{Ability, Item};
 use crate::Asset;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr};
 use std::path::Path;
 use std::{fs, io};
 
 /// File to load & store settings from
 const SETTINGS_FILE_NAME: &'static str = "settings.json";
 
+/// Length of the generated game state integration auth token
+const AUTH_TOKEN_LEN: usize = 32;
+
+/// Number of neutral item tiers
+const NEUTRAL_TIERS: usize = 5;
+
+/// Ability name matching any ultimate ability of the hero
+pub const ULTIMATE_ABILITY: &'static str = "ultimate";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(default)]
 pub struct Settings {
     pub global: GlobalConfig,
+    /// Default profile used by clients without any profile selected
+    #[serde(flatten)]
+    pub profile: Profile,
+    /// Named profiles clients can select
+    pub profiles: BTreeMap<String, Profile>,
+    pub clients: Vec<ClientConfig>,
+}
+
+/// Announcer configuration of single profile
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Profile {
     pub bounty_rune: SpawnConfig,
     pub power_rune: SpawnConfig,
     pub tomb_of_knowledge: SpawnConfig,
     pub observer_wards: NotifyConfig,
-    pub neutral_items: SpawnConfig,
+    pub neutral_items: NeutralItemsConfig,
+    pub roshan: RoshanConfig,
+    pub aegis: AegisConfig,
+    pub day_night: DayNightConfig,
     pub buyback_ready: NotifyConfig,
+    pub respawn: RespawnConfig,
+    pub low_health: ThresholdConfig,
+    pub low_mana: ThresholdConfig,
+    pub abilities: Vec<AbilityConfig>,
+    pub items: Vec<ItemConfig>,
+    pub midas: IdleConfig,
+    pub missing_tp: IdleConfig,
+    pub talents: TalentConfig,
+    pub timers: Vec<TimerConfig>,
+    pub match_started: NotifyConfig,
+    pub match_ended: NotifyConfig,
+    pub spectator: SpectatorConfig,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GlobalConfig {
     pub volume: f32,
     pub suspend_all: bool,
+    /// Token the game has to send with every game state update,
+    /// generated when settings are loaded without one
+    #[serde(default)]
+    pub auth_token: Option<String>,
+    /// Address the announcer listens on (after restart),
+    /// `0.0.0.0` accepts game clients from other machines
+    #[serde(default = "GlobalConfig::default_address")]
+    pub address: IpAddr,
+}
+
+/// Per game client configuration, client is identified by its steam id
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClientConfig {
+    pub id: String,
+    /// Name of the selected profile, default profile is used when not set
+    pub profile: Option<String>,
+    /// Name of the audio output device, default device is used when not set
+    pub output: Option<String>,
+}
+
+impl GlobalConfig {
+    fn default_address() -> IpAddr {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    }
+
+    /// No update is accepted until the token is generated
+    pub fn is_authorized(&self, token: Option<&str>) -> bool {
+        match (&self.auth_token, token) {
+            (Some(auth_token), Some(token)) => constant_time_eq(auth_token, token),
+            _ => false,
+        }
+    }
+}
+
+fn generate_auth_token() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(AUTH_TOKEN_LEN)
+        .collect()
+}
+
+/// Compares the tokens without leaking the position of the first difference
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
 }
 
 impl Settings {
     pub fn load() -> Self {
         let mut serialized = String::new();
-        std::env::current_dir()
+        let loaded = std::env::current_dir()
             .and_then(|cwd| File::open(cwd.join(SETTINGS_FILE_NAME)))
             .and_then(|mut f| f.read_to_string(&mut serialized))
             .and_then(|_| {
                 serde_json::from_str(&serialized)
                     .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
-            })
-            .unwrap_or(Settings::default())
+            });
+
+        // settings file which can't be read or parsed is never overwritten on load
+        let (mut settings, writable) = match loaded {
+            Ok(settings) => (settings, true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Settings::default(), true),
+            Err(e) => {
+                println!("Unable to load settings, using defaults: {}", e);
+                (Settings::default(), false)
+            }
+        };
+
+        if settings.global.auth_token.is_none() {
+            settings.global.auth_token = Some(generate_auth_token());
+            if writable {
+                if let Err(e) = settings.save() {
+                    println!("Unable to save generated auth token: {}", e);
+                }
+            }
+        }
+        settings
     }
 
     pub fn save(&self) -> io::Result<()> {
@@ -54,6 +162,43 @@ impl Settings {
         // once successful rename it
         fs::rename(stage_file_name, json_file_name)
     }
+
+    pub fn client(&self, id: &str) -> Option<&ClientConfig> {
+        self.clients.iter().find(|c| c.id == id)
+    }
+
+    /// Profile with given name, falls back to the default profile
+    pub fn profile(&self, name: Option<&str>) -> &Profile {
+        name.and_then(|name| self.profiles.get(name))
+            .unwrap_or(&self.profile)
+    }
+
+    /// Mutable profile with given name, the default profile when not set
+    pub fn profile_mut(&mut self, name: Option<&str>) -> Option<&mut Profile> {
+        match name {
+            Some(name) => self.profiles.get_mut(name),
+            None => Some(&mut self.profile),
+        }
+    }
+
+    pub fn auth_token(&self) -> Option<&str> {
+        self.global.auth_token.as_deref()
+    }
+
+    /// Copy safe to be sent to the web page, without the auth token
+    pub fn redacted(&self) -> Settings {
+        let mut settings = self.clone();
+        settings.global.auth_token = None;
+        settings
+    }
+
+    /// Generates and stores new auth token
+    pub fn rotate_auth_token(&mut self) -> io::Result<String> {
+        let token = generate_auth_token();
+        self.global.auth_token = Some(token.clone());
+        self.save()?;
+        Ok(token)
+    }
 }
 
 impl Default for Settings {
@@ -62,80 +207,239 @@ impl Default for Settings {
             global: GlobalConfig {
                 volume: 1.0,
                 suspend_all: false,
+                auth_token: None,
+                address: GlobalConfig::default_address(),
             },
+            profile: Profile::default(),
+            profiles: BTreeMap::new(),
+            clients: Vec::new(),
+        }
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile {
             bounty_rune: SpawnConfig {
-                notify: NotifyInfo {
-                    enabled: false,
-                    before_sec: 15,
-                    action: NotifyAction::Sound {
+                notify: NotifyInfo::new(
+                    false,
+                    15,
+                    NotifyAction::Sound {
                         sound: "bounty_rune.mp3".to_string(),
                     },
-                },
-                spawn: SpawnInfo {
+                ),
+                spawn: Schedule::Every(SpawnInfo {
                     first_sec: 0,
                     interval_sec: 300,
-                },
+                    until_sec: None,
+                }),
             },
 
             power_rune: SpawnConfig {
-                notify: NotifyInfo {
-                    enabled: false,
-                    before_sec: 10,
-                    action: NotifyAction::Sound {
+                notify: NotifyInfo::new(
+                    false,
+                    10,
+                    NotifyAction::Sound {
                         sound: "power_rune.mp3".to_string(),
                     },
-                },
-                spawn: SpawnInfo {
+                ),
+                spawn: Schedule::Every(SpawnInfo {
                     first_sec: 240,
                     interval_sec: 120,
-                },
+                    until_sec: None,
+                }),
             },
 
             tomb_of_knowledge: SpawnConfig {
-                notify: NotifyInfo {
-                    enabled: false,
-                    before_sec: 5,
-                    action: NotifyAction::Sound {
+                notify: NotifyInfo::new(
+                    false,
+                    5,
+                    NotifyAction::Sound {
                         sound: "tomb_of_knowledge.mp3".to_string(),
                     },
-                },
-                spawn: SpawnInfo {
+                ),
+                spawn: Schedule::Every(SpawnInfo {
                     first_sec: 600,
                     interval_sec: 600,
-                },
+                    until_sec: None,
+                }),
             },
 
             observer_wards: NotifyConfig {
-                notify: NotifyInfo {
-                    enabled: false,
-                    before_sec: 0,
-                    action: NotifyAction::Sound {
+                notify: NotifyInfo::new(
+                    false,
+                    0,
+                    NotifyAction::Sound {
                         sound: "observer_ward.mp3".to_string(),
                     },
-                },
+                ),
             },
 
-            neutral_items: SpawnConfig {
-                notify: NotifyInfo {
-                    enabled: false,
-                    before_sec: 0,
-                    action: NotifyAction::Sound {
+            neutral_items: NeutralItemsConfig {
+                notify: NotifyInfo::new(
+                    false,
+                    0,
+                    NotifyAction::Sound {
                         sound: "neutral_items.mp3".to_string(),
                     },
+                ),
+                tiers: NeutralTier::schedule(&[420, 1020, 1620, 2220, 3600]),
+            },
+
+            roshan: RoshanConfig {
+                min_respawn: NotifyConfig {
+                    notify: NotifyInfo::new(false, 30, NotifyAction::default()),
                 },
-                spawn: SpawnInfo {
-                    first_sec: 420,
-                    interval_sec: 600,
+                max_respawn: NotifyConfig {
+                    notify: NotifyInfo::new(false, 30, NotifyAction::default()),
+                },
+                respawn: RespawnWindow {
+                    min_sec: 480,
+                    max_sec: 660,
+                },
+            },
+
+            aegis: AegisConfig {
+                expires: NotifyConfig {
+                    notify: NotifyInfo::new(false, 60, NotifyAction::default())
+                        .with_offsets(&[30, 10]),
+                },
+            },
+
+            day_night: DayNightConfig {
+                night: NotifyConfig {
+                    notify: NotifyInfo::new(false, 10, NotifyAction::default()),
+                },
+                day: NotifyConfig {
+                    notify: NotifyInfo::new(false, 10, NotifyAction::default()),
                 },
             },
 
             buyback_ready: NotifyConfig {
-                notify: NotifyInfo {
-                    enabled: false,
-                    before_sec: 0,
-                    action: NotifyAction::Sound {
+                notify: NotifyInfo::new(
+                    false,
+                    0,
+                    NotifyAction::Sound {
                         sound: "buyback_ready.mp3".to_string(),
                     },
+                ),
+            },
+
+            respawn: RespawnConfig {
+                countdown: NotifyConfig {
+                    notify: NotifyInfo::new(false, 10, NotifyAction::default()).with_offsets(&[5]),
+                },
+                buyback: NotifyConfig {
+                    notify: NotifyInfo::new(
+                        false,
+                        0,
+                        NotifyAction::Sound {
+                            sound: "buyback_ready.mp3".to_string(),
+                        },
+                    ),
+                },
+            },
+
+            low_health: ThresholdConfig {
+                notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+                threshold: 25,
+                hysteresis: 10,
+                cooldown_sec: 30,
+            },
+
+            low_mana: ThresholdConfig {
+                notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+                threshold: 20,
+                hysteresis: 10,
+                cooldown_sec: 30,
+            },
+
+            abilities: vec![AbilityConfig {
+                ability: ULTIMATE_ABILITY.to_string(),
+                notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+            }],
+
+            items: Vec::new(),
+
+            midas: IdleConfig {
+                notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+                after_sec: 10,
+                repeat_sec: 30,
+            },
+
+            missing_tp: IdleConfig {
+                notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+                after_sec: 30,
+                repeat_sec: 0,
+            },
+
+            talents: TalentConfig {
+                level: NotifyConfig {
+                    notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+                },
+                unspent: IdleConfig {
+                    notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+                    after_sec: 30,
+                    repeat_sec: 60,
+                },
+                shard: NotifyConfig {
+                    notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+                },
+            },
+
+            timers: vec![
+                TimerConfig {
+                    id: "wisdom_rune".to_string(),
+                    label: "Wisdom rune".to_string(),
+                    config: SpawnConfig {
+                        notify: NotifyInfo::new(false, 15, NotifyAction::default()),
+                        spawn: Schedule::Every(SpawnInfo {
+                            first_sec: 420,
+                            interval_sec: 420,
+                            until_sec: None,
+                        }),
+                    },
+                },
+                TimerConfig {
+                    id: "lotus_pool".to_string(),
+                    label: "Lotus pool".to_string(),
+                    config: SpawnConfig {
+                        notify: NotifyInfo::new(false, 10, NotifyAction::default()),
+                        spawn: Schedule::Every(SpawnInfo {
+                            first_sec: 180,
+                            interval_sec: 180,
+                            until_sec: None,
+                        }),
+                    },
+                },
+            ],
+
+            match_started: NotifyConfig {
+                notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+            },
+
+            match_ended: NotifyConfig {
+                notify: NotifyInfo::new(false, 0, NotifyAction::default()),
+            },
+
+            spectator: SpectatorConfig {
+                buyback: EventNotifyConfig {
+                    notify: EventNotifyInfo {
+                        enabled: false,
+                        action: NotifyAction::Sound {
+                            sound: "buyback_ready.mp3".to_string(),
+                        },
+                    },
+                },
+                respawn: NotifyConfig {
+                    notify: NotifyInfo::new(false, 5, NotifyAction::default()),
+                },
+                net_worth: NetWorthConfig {
+                    notify: EventNotifyInfo {
+                        enabled: false,
+                        action: NotifyAction::default(),
+                    },
+                    swing: 5000,
                 },
             },
         }
@@ -143,47 +447,169 @@ impl Default for Settings {
 }
 
 pub trait OnClock {
-    fn on_clock(&self, clock_time: i32, player: &AudioPlayer) -> bool;
+    /// Triggers the actions of all notifications due in clock time interval (last, now],
+    /// returns their lead times
+    fn on_clock(&self, last: i32, now: i32, player: &AudioPlayer) -> Vec<u16>;
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SpawnConfig {
     pub notify: NotifyInfo,
-    pub spawn: SpawnInfo,
+    pub spawn: Schedule,
 }
 
 impl SpawnConfig {
-    fn can_invoke_action(&self, clock_time: i32) -> bool {
+    /// Notifications due in clock time interval (last, now], one for every spawn & lead time
+    fn due(&self, last: i32, now: i32) -> Vec<(u16, &NotifyAction)> {
+        let mut due = Vec::new();
         if !self.notify.enabled {
-            return false;
+            return due;
         }
 
-        let first_notify = self.spawn.first_sec as i32 - self.notify.before_sec as i32;
-        if clock_time < first_notify {
-            return false;
+        for (before_sec, action) in self.notify.leads() {
+            let before = before_sec as i32;
+            let mut after = last + before;
+            while let Some(spawn_time) = self.spawn.next_spawn(after) {
+                if spawn_time > now + before {
+                    break;
+                }
+                due.push((before_sec, action));
+                after = spawn_time;
+            }
         }
+        due
+    }
 
-        if clock_time == first_notify {
-            return true;
-        }
+    /// Whether any notification is due exactly at given clock time
+    #[cfg(test)]
+    fn can_invoke_action(&self, clock_time: i32) -> bool {
+        !self.due(clock_time - 1, clock_time).is_empty()
+    }
+}
+
+impl OnClock for SpawnConfig {
+    fn on_clock(&self, last: i32, now: i32, player: &AudioPlayer) -> Vec<u16> {
+        self.due(last, now)
+            .into_iter()
+            .map(|(before_sec, action)| {
+                action.trigger(player);
+                before_sec
+            })
+            .collect()
+    }
+}
 
-        let interval_reminder =
-            (clock_time - self.spawn.first_sec as i32) % self.spawn.interval_sec as i32;
+/// Neutral items drop from the neutral camps in tiers unlocked at given clock times
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "NeutralItemsForm")]
+pub struct NeutralItemsConfig {
+    pub notify: NotifyInfo,
+    pub tiers: Vec<NeutralTier>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NeutralTier {
+    pub tier: u8,
 
-        let action_reminder =
-            ((self.spawn.interval_sec - self.notify.before_sec) % self.spawn.interval_sec) as i32;
+    /// Clock time sec when the tier is unlocked
+    pub unlock_sec: u16,
+
+    /// Action of this tier, `notify.action` is used when not set
+    #[serde(default)]
+    pub action: Option<NotifyAction>,
+}
+
+impl NeutralTier {
+    /// Tier is to be notified about `before_sec` ahead in clock time interval (last, now]
+    fn is_due(&self, last: i32, now: i32, before_sec: u16) -> bool {
+        let notify_at = self.unlock_sec as i32 - before_sec as i32;
+        last < notify_at && notify_at <= now
+    }
 
-        interval_reminder == action_reminder
+    /// Tiers numbered from 1 unlocked at given clock times
+    pub fn schedule(unlock_secs: &[u16]) -> Vec<NeutralTier> {
+        unlock_secs
+            .iter()
+            .enumerate()
+            .map(|(i, unlock_sec)| NeutralTier {
+                tier: i as u8 + 1,
+                unlock_sec: *unlock_sec,
+                action: None,
+            })
+            .collect()
     }
 }
 
-impl OnClock for SpawnConfig {
-    fn on_clock(&self, clock_time: i32, player: &AudioPlayer) -> bool {
-        if self.can_invoke_action(clock_time) {
-            self.notify.action.trigger(player);
-            return true;
+/// Accepted forms of the neutral items in settings.json
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NeutralItemsForm {
+    Tiers {
+        notify: NotifyInfo,
+        tiers: Vec<NeutralTier>,
+    },
+    /// Fixed interval used by the older versions
+    Spawn(SpawnConfig),
+}
+
+impl From<NeutralItemsForm> for NeutralItemsConfig {
+    fn from(form: NeutralItemsForm) -> Self {
+        match form {
+            NeutralItemsForm::Tiers { notify, tiers } => NeutralItemsConfig { notify, tiers },
+            NeutralItemsForm::Spawn(SpawnConfig { notify, spawn }) => {
+                let mut unlock_secs = Vec::new();
+                let mut after = -1;
+                while let Some(unlock_sec) = spawn.next_spawn(after) {
+                    if unlock_secs.len() == NEUTRAL_TIERS {
+                        break;
+                    }
+                    unlock_secs.push(unlock_sec as u16);
+                    after = unlock_sec;
+                }
+                NeutralItemsConfig {
+                    notify,
+                    tiers: NeutralTier::schedule(&unlock_secs),
+                }
+            }
+        }
+    }
+}
+
+impl NeutralItemsConfig {
+    /// Triggers the actions of the tiers due in clock time interval (last, now],
+    /// returns the tiers with lead times
+    pub fn on_clock(&self, last: i32, now: i32, player: &AudioPlayer) -> Vec<(u8, u16)> {
+        if !self.notify.enabled {
+            return Vec::new();
+        }
+
+        let mut notified = Vec::new();
+        for tier in &self.tiers {
+            // tier action replaces the notification action, not the own actions of lead times
+            let action = tier.action.as_ref().unwrap_or(&self.notify.action);
+            for (before_sec, action) in self.notify.leads_with(action) {
+                if tier.is_due(last, now, before_sec) {
+                    action.trigger(player);
+                    notified.push((tier.tier, before_sec));
+                }
+            }
         }
-        false
+        notified
+    }
+}
+
+/// User defined timer (e.g. lotus pool, tormentor, stack or pull timing)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimerConfig {
+    pub id: String,
+    pub label: String,
+    #[serde(flatten)]
+    pub config: SpawnConfig,
+}
+
+impl OnClock for TimerConfig {
+    fn on_clock(&self, last: i32, now: i32, player: &AudioPlayer) -> Vec<u16> {
+        self.config.on_clock(last, now, player)
     }
 }
 
@@ -192,13 +618,226 @@ pub struct NotifyConfig {
     pub notify: NotifyInfo,
 }
 
+/// Notification of an event which can't be announced in advance
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventNotifyConfig {
+    pub notify: EventNotifyInfo,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RoshanConfig {
+    /// Roshan can respawn from now on
+    pub min_respawn: NotifyConfig,
+    /// Roshan is respawned at the latest
+    pub max_respawn: NotifyConfig,
+    pub respawn: RespawnWindow,
+}
+
+impl RoshanConfig {
+    /// Clock times of the respawn window start & end for roshan killed at given clock time
+    pub fn respawn_times(&self, killed_at: i32) -> (i32, i32) {
+        (
+            killed_at + self.respawn.min_sec as i32,
+            killed_at + self.respawn.max_sec as i32,
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RespawnWindow {
+    /// Clock time sec after the kill when the respawn window starts
+    pub min_sec: u16,
+
+    /// Clock time sec after the kill when the respawn window ends
+    pub max_sec: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AegisConfig {
+    /// Aegis expires in `before_sec` (and in each of the offsets)
+    pub expires: NotifyConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DayNightConfig {
+    /// Night starts in `before_sec`
+    pub night: NotifyConfig,
+    /// Day starts in `before_sec`
+    pub day: NotifyConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RespawnConfig {
+    /// Hero respawns in `before_sec` (and in each of the offsets)
+    pub countdown: NotifyConfig,
+    /// Hero died while the buyback is available
+    pub buyback: NotifyConfig,
+}
+
+/// Notifies when a value drops below the threshold
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ThresholdConfig {
+    pub notify: NotifyInfo,
+    pub threshold: u32,
+
+    /// Value has to rise this much above the threshold to re-arm the notification
+    pub hysteresis: u32,
+
+    /// Minimal clock time sec between two notifications
+    pub cooldown_sec: u16,
+}
+
+/// Announcements available only when spectating (or casting) a game
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpectatorConfig {
+    /// Any hero has buyback available again
+    pub buyback: EventNotifyConfig,
+    /// Any dead hero respawns in `before_sec`
+    pub respawn: NotifyConfig,
+    pub net_worth: NetWorthConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetWorthConfig {
+    pub notify: EventNotifyInfo,
+    /// Change of the net worth lead (radiant - dire) since last announcement
+    pub swing: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AbilityConfig {
+    /// Ability name as reported by the game (e.g. `invoker_sun_strike`)
+    /// or `ultimate` to match any ultimate ability
+    pub ability: String,
+    pub notify: NotifyInfo,
+}
+
+impl AbilityConfig {
+    fn matches(&self, ability: &Ability) -> bool {
+        match &ability.name {
+            Some(name) if *name == self.ability => true,
+            _ => self.ability == ULTIMATE_ABILITY && ability.ultimate.unwrap_or_default(),
+        }
+    }
+
+    /// Notifies about the matching ability coming off cooldown, returns the lead times
+    pub fn on_cooldown(
+        &self,
+        ability: &Ability,
+        previous_cooldown: u32,
+        player: &AudioPlayer,
+    ) -> Vec<u16> {
+        if !self.matches(ability) {
+            return Vec::new();
+        }
+
+        let cooldown = ability.cooldown.unwrap_or_default();
+        self.notify
+            .trigger_cooldown(player, previous_cooldown, cooldown)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ItemConfig {
+    /// Item name as reported by the game (e.g. `item_black_king_bar`)
+    pub item: String,
+    pub notify: NotifyInfo,
+}
+
+impl ItemConfig {
+    /// Notifies about the matching item coming off cooldown, returns the lead times
+    pub fn on_cooldown(
+        &self,
+        item: &Item,
+        previous_cooldown: u32,
+        player: &AudioPlayer,
+    ) -> Vec<u16> {
+        if item.name.as_deref() != Some(&self.item) {
+            return Vec::new();
+        }
+
+        let cooldown = item.cooldown.unwrap_or_default();
+        self.notify
+            .trigger_cooldown(player, previous_cooldown, cooldown)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TalentConfig {
+    /// Hero reached level with a new talent (10, 15, 20, 25) or the max level
+    pub level: NotifyConfig,
+    /// Talent available but not learned
+    pub unspent: IdleConfig,
+    /// Aghanim's Shard is available in the shop in `before_sec`
+    pub shard: NotifyConfig,
+}
+
+/// Notifies when a condition (e.g. castable Hand of Midas) holds for too long
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IdleConfig {
+    pub notify: NotifyInfo,
+
+    /// Clock time sec the condition has to hold before the first notification
+    pub after_sec: u16,
+
+    /// Interval in clock time sec of the repeated notifications, 0 to notify only once
+    pub repeat_sec: u16,
+}
+
+/// Clock times when something spawns
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Schedule {
+    /// Recurring spawns (e.g. power runes)
+    Every(SpawnInfo),
+    /// Spawns at explicit clock times only (e.g. water runes at 2:00 and 4:00)
+    At { at: Vec<u16> },
+    /// Union of several schedules (e.g. bounty runes changing interval mid-game)
+    Any { any: Vec<Schedule> },
+}
+
+impl Schedule {
+    /// First spawn after given clock time
+    pub fn next_spawn(&self, after: i32) -> Option<i32> {
+        match self {
+            Schedule::Every(spawn) => spawn.next_spawn(after),
+            Schedule::At { at } => at.iter().map(|t| *t as i32).filter(|t| *t > after).min(),
+            Schedule::Any { any } => any.iter().filter_map(|s| s.next_spawn(after)).min(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SpawnInfo {
     /// Clock time sec when the it is first spawned
     pub first_sec: u16,
 
-    /// Interval in clock time sec when next spawn will occure
+    /// Interval in clock time sec when next spawn will occure, 0 to spawn only once
     pub interval_sec: u16,
+
+    /// Clock time sec of the last possible spawn, spawns forever when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until_sec: Option<u16>,
+}
+
+impl SpawnInfo {
+    pub fn next_spawn(&self, after: i32) -> Option<i32> {
+        let first = self.first_sec as i32;
+        let interval = self.interval_sec as i32;
+
+        let next = if after < first {
+            first
+        } else if interval == 0 {
+            return None;
+        } else {
+            first + ((after - first) / interval + 1) * interval
+        };
+
+        match self.until_sec {
+            Some(until) if next > until as i32 => None,
+            _ => Some(next),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -206,6 +845,95 @@ pub struct NotifyInfo {
     pub enabled: bool,
     pub before_sec: u16,
     pub action: NotifyAction,
+
+    /// Additional lead times (e.g. sound 30 sec before and beep 5 sec before)
+    #[serde(default)]
+    pub offsets: Vec<NotifyOffset>,
+}
+
+impl NotifyInfo {
+    pub fn new(enabled: bool, before_sec: u16, action: NotifyAction) -> Self {
+        NotifyInfo {
+            enabled,
+            before_sec,
+            action,
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Adds lead times using the action of the notification
+    pub fn with_offsets(mut self, before_secs: &[u16]) -> Self {
+        self.offsets
+            .extend(before_secs.iter().map(|before_sec| NotifyOffset {
+                before_sec: *before_sec,
+                action: None,
+            }));
+        self
+    }
+
+    /// All lead times with their actions, `before_sec` goes first
+    pub fn leads(&self) -> impl Iterator<Item = (u16, &NotifyAction)> {
+        self.leads_with(&self.action)
+    }
+
+    /// All lead times with `action` used instead of the action of the notification
+    pub fn leads_with<'a>(
+        &'a self,
+        action: &'a NotifyAction,
+    ) -> impl Iterator<Item = (u16, &'a NotifyAction)> {
+        std::iter::once((self.before_sec, action)).chain(
+            self.offsets
+                .iter()
+                .map(move |o| (o.before_sec, o.action.as_ref().unwrap_or(action))),
+        )
+    }
+
+    /// Triggers the action of every lead time matching the condition, returns the lead times
+    pub fn trigger_leads(
+        &self,
+        player: &AudioPlayer,
+        mut matches: impl FnMut(u16) -> bool,
+    ) -> Vec<u16> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        self.leads()
+            .filter(|(before_sec, _)| matches(*before_sec))
+            .map(|(before_sec, action)| {
+                action.trigger(player);
+                before_sec
+            })
+            .collect()
+    }
+
+    /// Triggers the actions of all lead times the cooldown got under, returns the lead times
+    pub fn trigger_cooldown(
+        &self,
+        player: &AudioPlayer,
+        previous_cooldown: u32,
+        cooldown: u32,
+    ) -> Vec<u16> {
+        self.trigger_leads(player, |before_sec| {
+            previous_cooldown > before_sec as u32 && cooldown <= before_sec as u32
+        })
+    }
+}
+
+/// Like `NotifyInfo` without the lead times
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventNotifyInfo {
+    pub enabled: bool,
+    pub action: NotifyAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotifyOffset {
+    pub before_sec: u16,
+
+    /// Action of this lead time, action of the notification is used when not set
+    #[serde(default)]
+    pub action: Option<NotifyAction>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -256,19 +984,26 @@ impl Default for NotifyAction {
 
 #[cfg(test)]
 mod tests {
-    use crate::settings::{NotifyAction, NotifyInfo, SpawnConfig, SpawnInfo};
+    use crate::settings::{
+        NeutralItemsConfig, NotifyAction, NotifyInfo, Schedule, Settings, SpawnConfig, SpawnInfo,
+        TimerConfig,
+    };
 
     fn new_sc(enabled: bool, before_sec: u16, first_sec: u16, interval_sec: u16) -> SpawnConfig {
         SpawnConfig {
-            notify: NotifyInfo {
-                enabled,
-                before_sec,
-                action: NotifyAction::default(),
-            },
-            spawn: SpawnInfo {
+            notify: NotifyInfo::new(enabled, before_sec, NotifyAction::default()),
+            spawn: Schedule::Every(SpawnInfo {
                 first_sec,
                 interval_sec,
-            },
+                until_sec: None,
+            }),
+        }
+    }
+
+    fn new_schedule(json: &str) -> SpawnConfig {
+        SpawnConfig {
+            notify: NotifyInfo::new(true, 2, NotifyAction::default()),
+            spawn: serde_json::from_str(json).unwrap(),
         }
     }
 
@@ -353,4 +1088,153 @@ mod tests {
         assert_eq!(cfg.can_invoke_action(15), false);
         assert_eq!(cfg.can_invoke_action(16), false);
     }
+
+    #[test]
+    fn interval_until_sec() {
+        let cfg = new_schedule(r#"{"first_sec": 0, "interval_sec": 10, "until_sec": 20}"#);
+        assert_eq!(cfg.can_invoke_action(-2), true);
+        assert_eq!(cfg.can_invoke_action(8), true);
+        assert_eq!(cfg.can_invoke_action(18), true);
+        assert_eq!(cfg.can_invoke_action(28), false);
+    }
+
+    #[test]
+    fn interval_sec_0_spawns_once() {
+        let cfg = new_schedule(r#"{"first_sec": 10, "interval_sec": 0}"#);
+        assert_eq!(cfg.can_invoke_action(8), true);
+        assert_eq!(cfg.can_invoke_action(18), false);
+    }
+
+    #[test]
+    fn explicit_times() {
+        let cfg = new_schedule(r#"{"at": [120, 240]}"#);
+        assert_eq!(cfg.can_invoke_action(117), false);
+        assert_eq!(cfg.can_invoke_action(118), true);
+        assert_eq!(cfg.can_invoke_action(238), true);
+        assert_eq!(cfg.can_invoke_action(358), false);
+    }
+
+    #[test]
+    fn union_of_schedules() {
+        let cfg = new_schedule(
+            r#"{"any": [
+                {"first_sec": 0, "interval_sec": 180, "until_sec": 360},
+                {"first_sec": 600, "interval_sec": 300},
+                {"at": [450]}
+            ]}"#,
+        );
+        assert_eq!(cfg.can_invoke_action(178), true);
+        assert_eq!(cfg.can_invoke_action(358), true);
+        assert_eq!(cfg.can_invoke_action(448), true);
+        assert_eq!(cfg.can_invoke_action(538), false);
+        assert_eq!(cfg.can_invoke_action(598), true);
+        assert_eq!(cfg.can_invoke_action(898), true);
+        assert_eq!(cfg.can_invoke_action(1078), false);
+    }
+
+    #[test]
+    fn custom_timer() {
+        let timer: TimerConfig = serde_json::from_str(
+            r#"{
+                "id": "tormentor",
+                "label": "Tormentor",
+                "notify": {"enabled": true, "before_sec": 30, "action": {"type": "beep", "duration_ms": 100, "freq": 400}},
+                "spawn": {"first_sec": 1200, "interval_sec": 600}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(timer.config.can_invoke_action(1170), true);
+        assert_eq!(timer.config.can_invoke_action(1770), true);
+        assert_eq!(timer.config.can_invoke_action(1200), false);
+    }
+
+    #[test]
+    fn multiple_offsets() {
+        let cfg: SpawnConfig = serde_json::from_str(
+            r#"{
+                "notify": {
+                    "enabled": true,
+                    "before_sec": 30,
+                    "action": {"type": "sound", "sound": "power_rune.mp3"},
+                    "offsets": [{"before_sec": 10}, {"before_sec": 5, "action": {"type": "beep", "duration_ms": 100, "freq": 400}}]
+                },
+                "spawn": {"first_sec": 240, "interval_sec": 120}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.can_invoke_action(210), true);
+        assert_eq!(cfg.can_invoke_action(230), true);
+        assert_eq!(cfg.can_invoke_action(235), true);
+        assert_eq!(cfg.can_invoke_action(236), false);
+        assert_eq!(cfg.can_invoke_action(355), true);
+
+        let actions: Vec<bool> = cfg
+            .notify
+            .leads()
+            .map(|(_, action)| match action {
+                NotifyAction::Beep { .. } => true,
+                _ => false,
+            })
+            .collect();
+        assert_eq!(actions, vec![false, false, true]);
+    }
+
+    #[test]
+    fn skipped_and_repeated_seconds() {
+        let cfg = new_sc(true, 0, 105, 10);
+        assert_eq!(cfg.due(100, 104).len(), 0);
+        // clock jumped over the spawn second
+        assert_eq!(cfg.due(104, 106).len(), 1);
+        // same second arrived again
+        assert_eq!(cfg.due(106, 106).len(), 0);
+        assert_eq!(cfg.due(106, 114).len(), 0);
+        assert_eq!(cfg.due(114, 115).len(), 1);
+        // every spawn crossed fires once
+        assert_eq!(cfg.due(100, 130).len(), 3);
+    }
+
+    #[test]
+    fn neutral_items_tiers() {
+        let cfg: NeutralItemsConfig = serde_json::from_str(
+            r#"{
+                "notify": {"enabled": true, "before_sec": 5, "action": {"type": "beep", "duration_ms": 100, "freq": 400}},
+                "tiers": [{"tier": 1, "unlock_sec": 420}, {"tier": 2, "unlock_sec": 1020}]
+            }"#,
+        )
+        .unwrap();
+        let tier_to_notify = |clock_time: i32| {
+            cfg.tiers
+                .iter()
+                .find(|t| t.is_due(clock_time - 1, clock_time, cfg.notify.before_sec))
+                .map(|t| t.tier)
+        };
+        assert_eq!(tier_to_notify(414), None);
+        assert_eq!(tier_to_notify(415), Some(1));
+        assert_eq!(tier_to_notify(420), None);
+        assert_eq!(tier_to_notify(1015), Some(2));
+        assert_eq!(tier_to_notify(1615), None);
+    }
+
+    #[test]
+    fn neutral_items_from_spawn_config() {
+        let cfg: NeutralItemsConfig =
+            serde_json::from_value(serde_json::to_value(new_sc(true, 0, 420, 600)).unwrap())
+                .unwrap();
+        let unlock_secs: Vec<u16> = cfg.tiers.iter().map(|t| t.unlock_sec).collect();
+        assert_eq!(unlock_secs, vec![420, 1020, 1620, 2220, 2820]);
+        assert_eq!(cfg.tiers[4].tier, 5);
+    }
+
+    #[test]
+    fn auth_token() {
+        let mut global = Settings::default().global;
+        assert_eq!(global.is_authorized(None), false);
+        assert_eq!(global.is_authorized(Some("")), false);
+
+        global.auth_token = Some("secret".to_string());
+        assert_eq!(global.is_authorized(Some("secret")), true);
+        assert_eq!(global.is_authorized(Some("secreT")), false);
+        assert_eq!(global.is_authorized(Some("secret2")), false);
+        assert_eq!(global.is_authorized(None), false);
+    }
 }
@@ -0,0 +1,160 @@
+use crate::game::Game;
+use serde_json::{Map, Value};
+
+/// Sections describing single update only, they are not merged into the snapshot
+const UPDATE_ONLY_SECTIONS: [&'static str; 3] = ["auth", "previously", "added"];
+
+/// Complete current view of the game merged from all partial game state updates
+pub struct GameSnapshot {
+    state: Value,
+    game: Game,
+}
+
+impl GameSnapshot {
+    pub fn new() -> Self {
+        GameSnapshot {
+            state: Value::Object(Map::new()),
+            game: Game::default(),
+        }
+    }
+
+    /// Deep merges the update into the snapshot, only sections present in the update are parsed
+    pub fn merge(&mut self, update: &Value) -> serde_json::Result<()> {
+        let sections = match update {
+            Value::Object(sections) => sections,
+            _ => return Ok(()),
+        };
+
+        let state = self
+            .state
+            .as_object_mut()
+            .expect("snapshot state is an object");
+        let previously = sections.get("previously");
+        for (section, value) in sections {
+            if UPDATE_ONLY_SECTIONS.contains(&section.as_str()) {
+                continue;
+            }
+
+            let merged = state.entry(section.clone()).or_insert(Value::Null);
+            merge_value(merged, value, previously.and_then(|p| p.get(section)));
+            self.game.set_section(section, merged)?;
+        }
+
+        // removed sections (e.g. `map` after leaving the match) are present only in `previously`
+        if let Some(Value::Object(previously)) = previously {
+            for section in previously.keys() {
+                if !sections.contains_key(section) && state.remove(section).is_some() {
+                    self.game.set_section(section, &Value::Null)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+}
+
+/// Deep merges the update, keys present in `previously` but missing in the update are removed
+fn merge_value(target: &mut Value, update: &Value, previously: Option<&Value>) {
+    match (target, update) {
+        (Value::Object(target), Value::Object(update)) => {
+            for (key, value) in update {
+                merge_value(
+                    target.entry(key.clone()).or_insert(Value::Null),
+                    value,
+                    previously.and_then(|p| p.get(key)),
+                );
+            }
+
+            if let Some(Value::Object(previously)) = previously {
+                for key in previously.keys() {
+                    if !update.contains_key(key) {
+                        target.remove(key);
+                    }
+                }
+            }
+        }
+        (target, update) => *target = update.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::snapshot::GameSnapshot;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_sections_missing_in_update() {
+        let mut snapshot = GameSnapshot::new();
+        snapshot
+            .merge(&json!({
+                "map": {"clock_time": 10, "game_state": "DOTA_GAMERULES_STATE_GAME_IN_PROGRESS"},
+                "hero": {"level": 3, "alive": true}
+            }))
+            .unwrap();
+        snapshot
+            .merge(&json!({
+                "map": {"clock_time": 11},
+                "previously": {"map": {"clock_time": 10}}
+            }))
+            .unwrap();
+
+        let game = snapshot.game();
+        assert_eq!(game.clock_time(), Some(11));
+        assert_eq!(game.in_game(), true);
+        assert_eq!(game.hero.as_ref().and_then(|h| h.level), Some(3));
+        assert_eq!(game.previously.map.is_none(), true);
+    }
+
+    #[test]
+    fn removes_sections_missing_in_update() {
+        let mut snapshot = GameSnapshot::new();
+        snapshot
+            .merge(&json!({
+                "map": {"clock_time": 10},
+                "hero": {"level": 3}
+            }))
+            .unwrap();
+        snapshot
+            .merge(&json!({
+                "hero": {"level": 4},
+                "previously": {"map": {"clock_time": 10}, "hero": {"level": 3}}
+            }))
+            .unwrap();
+
+        let game = snapshot.game();
+        assert_eq!(game.map.is_none(), true);
+        assert_eq!(game.hero.as_ref().and_then(|h| h.level), Some(4));
+    }
+
+    #[test]
+    fn removes_nested_keys_missing_in_update() {
+        let mut snapshot = GameSnapshot::new();
+        snapshot
+            .merge(&json!({
+                "items": {"slot0": {"name": "item_black_king_bar", "cooldown": 50, "can_cast": false}},
+                "hero": {"level": 3, "team2": {"player0": {"level": 5}}}
+            }))
+            .unwrap();
+        snapshot
+            .merge(&json!({
+                "items": {"slot0": {"name": "empty"}},
+                "hero": {"level": 4},
+                "previously": {
+                    "items": {"slot0": {"name": "item_black_king_bar", "cooldown": 50, "can_cast": false}},
+                    "hero": {"level": 3, "team2": {"player0": {"level": 5}}}
+                }
+            }))
+            .unwrap();
+
+        let game = snapshot.game();
+        let slot0 = game.items.as_ref().and_then(|i| i.slot("slot0")).unwrap();
+        assert_eq!(slot0.name.as_deref(), Some("empty"));
+        assert_eq!(slot0.cooldown, None);
+        let hero = game.hero.as_ref().unwrap();
+        assert_eq!(hero.level, Some(4));
+        assert_eq!(hero.team2.is_none(), true);
+    }
+}
@@ -1,25 +1,37 @@
+const SOUND_OPTIONS = [
+    { label: "Bounty rune",         value: "bounty_rune.mp3" },
+    { label: "Power rune",          value: "power_rune.mp3" },
+    { label: "Neutral items",       value: "neutral_items.mp3" },
+    { label: "Observer ward",       value: "observer_ward.mp3" },
+    { label: "Tomb of knowledge",   value: "tomb_of_knowledge.mp3" },
+    { label: "Buyback ready",       value: "buyback_ready.mp3" }
+]
+
 Vue.component('notify-config', {
     props: {
         title: String,
-        config: Object
+        config: Object,
+        // notification can be announced in advance
+        lead: { type: Boolean, default: true }
     },
 
     data() {
         return {
-            sound_options: [
-                { label: "Bounty rune",         value: "bounty_rune.mp3" },
-                { label: "Power rune",          value: "power_rune.mp3" },
-                { label: "Neutral items",       value: "neutral_items.mp3" },
-                { label: "Observer ward",       value: "observer_ward.mp3" },
-                { label: "Tomb of knowledge",   value: "tomb_of_knowledge.mp3" },
-                { label: "Buyback ready",       value: "buyback_ready.mp3" }
-            ]
+            // extra lead time either beeps or uses the notify action
+            beep: { type: "beep", duration_ms: 100, freq: 600 },
+            sound_options: SOUND_OPTIONS
         }
     },
 
     methods: {
         async trigger(event) {
             await axios.post('/api/trigger', this.config.notify.action)
+        },
+        addOffset() {
+            if (!this.config.notify.offsets) {
+                this.$set(this.config.notify, 'offsets', [])
+            }
+            this.config.notify.offsets.push({ before_sec: 0, action: null })
         }
     },
 
@@ -45,8 +57,14 @@ Vue.component('notify-config', {
                 </el-form-item>
                 <el-row type="flex">
                     <el-col :span="6">                  
-                        <el-form-item label="Notify before">
+                        <el-form-item v-if="lead" label="Notify before">
                             <el-input-number v-model="config.notify.before_sec" :min="0" :max="60"/> [s]
+                            <el-button @click="addOffset" icon="el-icon-plus" type="text">Add lead time</el-button>
+                        </el-form-item>
+                        <el-form-item v-if="lead" v-for="(offset, index) in config.notify.offsets" :key="index" label="Also before">
+                            <el-input-number v-model="offset.before_sec" :min="0" :max="60"/> [s]
+                            <el-switch :value="!!offset.action" @change="offset.action = $event ? Object.assign({}, beep) : null" active-text="Beep"/>
+                            <el-button @click="config.notify.offsets.splice(index, 1)" icon="el-icon-delete" type="text"/>
                         </el-form-item>
                         
                         <template v-if="config.notify.action">
@@ -95,14 +113,15 @@ Vue.component('notify-config', {
                         </el-form-item>
                     </el-col>
                     <el-col :span="6">
-                        <template v-if="config.spawn">
+                        <template v-if="config.spawn && config.spawn.interval_sec !== undefined">
                             <el-form-item label="Spawn interval">
-                                <el-input-number v-model="config.spawn.interval_sec" :min="1" :max="600" /> [s]
+                                <el-input-number v-model="config.spawn.interval_sec" :min="0" :max="7200" /> [s] (0 = once)
                             </el-form-item>
                             <el-form-item label="Spawn first">
-                                <el-input-number v-model="config.spawn.first_sec" :min="0" :max="600" /> [s]
+                                <el-input-number v-model="config.spawn.first_sec" :min="0" :max="7200" /> [s]
                             </el-form-item>
-                        </template>                   
+                        </template>
+                        <slot></slot>
                     </el-col>
                 </el-row>
             </el-form>
@@ -110,6 +129,280 @@ Vue.component('notify-config', {
     `
 })
 
+// own action of a neutral items tier, notification action is used when not set
+Vue.component('tier-action', {
+    props: {
+        tier: Object
+    },
+
+    data() {
+        return {
+            sound_options: SOUND_OPTIONS
+        }
+    },
+
+    computed: {
+        selected: {
+            get() {
+                const action = this.tier.action
+                if (!action) {
+                    return ""
+                }
+                return action.type == 'sound' ? action.sound : action.type
+            },
+            set(value) {
+                let action = null
+                if (value == 'playfile') {
+                    // file is set in settings.json only
+                    return
+                } else if (value == 'beep') {
+                    action = { type: "beep", duration_ms: 100, freq: 600 }
+                } else if (value) {
+                    action = { type: "sound", sound: value }
+                }
+                this.$set(this.tier, 'action', action)
+            }
+        }
+    },
+
+    methods: {
+        async trigger(event) {
+            await axios.post('/api/trigger', this.tier.action)
+        }
+    },
+
+    template: `
+        <span>
+            <el-select v-model="selected">
+                <el-option label="Notification action" value=""/>
+                <el-option
+                  v-for="sound in sound_options"
+                  :key="sound.value"
+                  :label="sound.label"
+                  :value="sound.value">
+                </el-option>
+                <el-option label="Beep" value="beep"/>
+                <el-option v-if="tier.action && tier.action.type == 'playfile'" label="Play file" value="playfile"/>
+            </el-select>
+            <el-button @click="trigger" icon="el-icon-video-play" :disabled="!tier.action"/>
+        </span>
+    `
+})
+
+Vue.component('abilities-config', {
+    props: {
+        abilities: Array
+    },
+
+    methods: {
+        add() {
+            this.abilities.push({
+                ability: "",
+                notify: {
+                    enabled: true,
+                    before_sec: 0,
+                    action: { type: "beep", duration_ms: 100, freq: 400 }
+                }
+            })
+        },
+        remove(index) {
+            this.abilities.splice(index, 1)
+        }
+    },
+
+    template: `
+        <div>
+            <div v-for="(config, index) in abilities" :key="index" style="margin-bottom: 20px">
+                <el-form label-position="right" label-width="110px" :model="config">
+                    <el-form-item label="Ability">
+                        <el-input v-model="config.ability" placeholder="ultimate" style="width: 300px"/>
+                        <el-button @click="remove(index)" icon="el-icon-delete" type="danger" plain/>
+                    </el-form-item>
+                </el-form>
+                <notify-config :config="config" @do-save="$emit('do-save')"/>
+            </div>
+            <el-button @click="add" icon="el-icon-plus">Add ability</el-button>
+        </div>
+    `
+})
+
+Vue.component('items-config', {
+    props: {
+        items: Array
+    },
+
+    methods: {
+        add() {
+            this.items.push({
+                item: "",
+                notify: {
+                    enabled: true,
+                    before_sec: 0,
+                    action: { type: "beep", duration_ms: 100, freq: 400 }
+                }
+            })
+        },
+        remove(index) {
+            this.items.splice(index, 1)
+        }
+    },
+
+    template: `
+        <div>
+            <div v-for="(config, index) in items" :key="index" style="margin-bottom: 20px">
+                <el-form label-position="right" label-width="110px" :model="config">
+                    <el-form-item label="Item">
+                        <el-input v-model="config.item" placeholder="item_black_king_bar" style="width: 300px"/>
+                        <el-button @click="remove(index)" icon="el-icon-delete" type="danger" plain/>
+                    </el-form-item>
+                </el-form>
+                <notify-config :config="config" @do-save="$emit('do-save')"/>
+            </div>
+            <el-button @click="add" icon="el-icon-plus">Add item</el-button>
+        </div>
+    `
+})
+
+Vue.component('timers-config', {
+    props: {
+        timers: Array
+    },
+
+    methods: {
+        add() {
+            this.timers.push({
+                id: "timer_" + Date.now(),
+                label: "",
+                notify: {
+                    enabled: true,
+                    before_sec: 10,
+                    action: { type: "beep", duration_ms: 100, freq: 400 }
+                },
+                spawn: { first_sec: 0, interval_sec: 60 }
+            })
+        },
+        remove(index) {
+            this.timers.splice(index, 1)
+        },
+        // explicit spawn times edited as comma separated seconds
+        times(timer) {
+            return (timer.spawn.at || []).join(", ")
+        },
+        setTimes(timer, value) {
+            const at = value.split(",").map(t => parseInt(t.trim())).filter(t => !isNaN(t))
+            this.$set(timer, "spawn", { at })
+        },
+        setInterval(timer) {
+            this.$set(timer, "spawn", { first_sec: 0, interval_sec: 60 })
+        }
+    },
+
+    template: `
+        <div>
+            <div v-for="(timer, index) in timers" :key="timer.id" style="margin-bottom: 20px">
+                <el-form label-position="right" label-width="110px" :model="timer">
+                    <el-form-item label="Timer">
+                        <el-input v-model="timer.label" placeholder="Tormentor" style="width: 300px"/>
+                        <el-button @click="remove(index)" icon="el-icon-delete" type="danger" plain/>
+                    </el-form-item>
+                </el-form>
+                <notify-config :config="timer" @do-save="$emit('do-save')">
+                    <template v-if="timer.spawn.at">
+                        <el-form-item label="Spawn at">
+                            <el-input :value="times(timer)" @change="setTimes(timer, $event)" placeholder="120, 240"/> [s]
+                        </el-form-item>
+                        <el-form-item>
+                            <el-button @click="setInterval(timer)" type="text">Use interval</el-button>
+                        </el-form-item>
+                    </template>
+                    <el-form-item v-else-if="timer.spawn.interval_sec !== undefined">
+                        <el-button @click="setTimes(timer, '')" type="text">Use explicit times</el-button>
+                    </el-form-item>
+                    <el-form-item v-else label="Schedule">
+                        <i>edited in settings.json</i>
+                    </el-form-item>
+                </notify-config>
+            </div>
+            <el-button @click="add" icon="el-icon-plus">Add timer</el-button>
+        </div>
+    `
+})
+
+Vue.component('clients-config', {
+    props: {
+        settings: Object
+    },
+
+    data() {
+        return {
+            clients: [],
+            outputs: []
+        }
+    },
+
+    methods: {
+        config(id) {
+            return this.settings.clients.find(c => c.id === id)
+        },
+        async doLoad() {
+            try {
+                this.clients = (await axios.get('/api/clients')).data;
+                this.outputs = (await axios.get('/api/outputs')).data;
+                for (const client of this.clients) {
+                    if (!this.config(client.id)) {
+                        this.settings.clients.push({ id: client.id, profile: null, output: null })
+                    }
+                }
+            } catch (e) {
+                this.$message({
+                    showClose: true,
+                    message: 'Unable to load clients',
+                    type: 'error'
+                });
+                console.error("Failed to load clients", e)
+            }
+        }
+    },
+
+    async created() {
+        await this.doLoad()
+    },
+
+    template: `
+        <el-card class="box-card">
+            <div slot="header" class="clearfix">
+                <span>Game clients</span>
+                <el-button @click="doLoad" icon="el-icon-refresh" style="float: right; padding: 3px 0" type="text">Refresh</el-button>
+            </div>
+            <el-table :data="settings.clients" empty-text="No game client connected yet">
+                <el-table-column label="Player">
+                    <template slot-scope="scope">
+                        <template v-for="client in clients" v-if="client.id === scope.row.id">
+                            {{client.name}} <span style="font-size: smaller">({{client.address}}, {{client.state}})</span>
+                        </template>
+                    </template>
+                </el-table-column>
+                <el-table-column prop="id" label="Id"/>
+                <el-table-column label="Profile">
+                    <template slot-scope="scope">
+                        <el-select v-model="scope.row.profile" placeholder="Default" clearable>
+                            <el-option v-for="(p, name) in settings.profiles" :key="name" :label="name" :value="name"></el-option>
+                        </el-select>
+                    </template>
+                </el-table-column>
+                <el-table-column label="Output">
+                    <template slot-scope="scope">
+                        <el-select v-model="scope.row.output" placeholder="Default" clearable>
+                            <el-option v-for="output in outputs" :key="output" :label="output" :value="output"></el-option>
+                        </el-select>
+                    </template>
+                </el-table-column>
+            </el-table>
+            <el-button type="primary" @click="$emit('do-save')" style="margin-top: 20px">Save</el-button>
+        </el-card>
+    `
+})
+
 Vue.component('install', {
 
     data() {
@@ -139,6 +432,23 @@ Vue.component('install', {
                 console.error("Failed to install game state integration file", e)
             }
         },
+        async doRotate(event) {
+            try {
+                await axios.post('/api/auth/rotate')
+                this.$message({
+                    showClose: true,
+                    message: 'Auth token rotated, restart Dota2 to apply it',
+                    type: 'success'
+                });
+            } catch (e) {
+                this.$message({
+                    showClose: true,
+                    message: 'Unable to rotate auth token',
+                    type: 'error'
+                });
+                console.error("Failed to rotate auth token", e)
+            }
+        },
         async doLoad() {
             const loading = this.$loading({
                 lock: true,
@@ -172,6 +482,7 @@ Vue.component('install', {
             <div v-if="install.dota_announcer_integration_file_exists">
                 <el-button icon="el-icon-document-checked" type="success" round>Installed</el-button>
                 <p>Game state integration file is installed correctly</p>
+                <el-button icon="el-icon-refresh" type="warning" plain @click="doRotate">Rotate auth token</el-button>
             </div>
             <div v-else-if="!install.dota_dir">
                 <p>Sorry but there is no Dota2 detected in the system</p>
@@ -190,6 +501,7 @@ Vue.component('install', {
                             <li>Download file <el-link type="primary" icon="el-icon-download" :href="install.announcer_integration_file_name">{{install.announcer_integration_file_name}}</el-link></li>
                             <li>Copy it to the folder<br/><span style="font-size: smaller">{{install.dota_gamestate_integration_dir}}</span></li>
                             <li>When done refresh this page</li>
+                            <li>Download the file again whenever the auth token is rotated</li>
                         </ol>
                     </el-collapse-item>
                 </el-collapse>